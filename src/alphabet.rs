@@ -0,0 +1,97 @@
+use std::collections::{HashMap, HashSet};
+
+// What a single character of a puzzle's directions stands for
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Symbol<T> {
+    Move(T),
+    Ignore,
+}
+
+// Which characters of a puzzle's directions make a move, and which move. Characters that
+// are neither a move nor ignored are an error for the day reading them, see `stream::Chars`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Alphabet<T> {
+    moves: HashMap<char, T>,
+    ignored: HashSet<char>,
+    ignore_whitespace: bool,
+}
+
+impl<T> Alphabet<T> {
+    pub fn empty() -> Self {
+        Self {
+            moves: HashMap::new(),
+            ignored: HashSet::new(),
+            ignore_whitespace: false,
+        }
+    }
+
+    pub fn new(moves: impl IntoIterator<Item = (char, T)>) -> Self {
+        Self::empty().with_moves(moves)
+    }
+
+    // Same as `new`, but a trailing newline or other whitespace is not an error
+    pub fn lenient(moves: impl IntoIterator<Item = (char, T)>) -> Self {
+        Self::new(moves).ignoring_whitespace()
+    }
+
+    pub fn with_move(mut self, ch: char, to: T) -> Self {
+        self.ignored.remove(&ch);
+        self.moves.insert(ch, to);
+        self
+    }
+
+    pub fn with_moves(self, moves: impl IntoIterator<Item = (char, T)>) -> Self {
+        moves
+            .into_iter()
+            .fold(self, |alphabet, (ch, to)| alphabet.with_move(ch, to))
+    }
+
+    pub fn ignoring(mut self, ch: char) -> Self {
+        self.moves.remove(&ch);
+        self.ignored.insert(ch);
+        self
+    }
+
+    pub fn ignoring_whitespace(mut self) -> Self {
+        self.ignore_whitespace = true;
+        self
+    }
+}
+
+impl<T: Copy> Alphabet<T> {
+    pub fn symbol(&self, ch: char) -> Option<Symbol<T>> {
+        if let Some(&to) = self.moves.get(&ch) {
+            Some(Symbol::Move(to))
+        } else if self.ignored.contains(&ch) || (self.ignore_whitespace && ch.is_whitespace()) {
+            Some(Symbol::Ignore)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Alphabet, Symbol};
+    use test_case::test_case;
+
+    const UP_DOWN: [(char, i32); 2] = [('u', 1), ('d', -1)];
+
+    #[test_case(Alphabet::new(UP_DOWN), 'd', Some(Symbol::Move(-1)); "down")]
+    #[test_case(Alphabet::new(UP_DOWN), '\n', None; "newline")]
+    #[test_case(Alphabet::new(UP_DOWN), 'x', None; "unknown")]
+    #[test_case(Alphabet::lenient(UP_DOWN), '\n', Some(Symbol::Ignore); "lenient newline")]
+    #[test_case(Alphabet::lenient(UP_DOWN), 'x', None; "lenient unknown")]
+    #[test_case(Alphabet::new(UP_DOWN).ignoring('u'), 'u', Some(Symbol::Ignore); "ignored")]
+    #[test_case(Alphabet::new(UP_DOWN).with_move('U', 10), 'U', Some(Symbol::Move(10)); "added")]
+    #[test_case(Alphabet::empty(), 'u', None; "empty")]
+    fn symbol(alphabet: Alphabet<i32>, ch: char, expected: Option<Symbol<i32>>) {
+        assert_eq!(alphabet.symbol(ch), expected);
+    }
+
+    #[test]
+    fn move_after_ignore() {
+        let alphabet = Alphabet::empty().ignoring('x').with_move('x', (1, 0));
+        assert_eq!(alphabet.symbol('x'), Some(Symbol::Move((1, 0))));
+    }
+}
@@ -0,0 +1,160 @@
+use crate::Answer;
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use std::fmt::Display;
+use std::io::ErrorKind;
+use std::path::Path;
+
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+// Known-correct answers, recorded in an answers.toml next to a day's input.txt
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExpectedAnswers {
+    part1: Option<Answer>,
+    part2: Option<Answer>,
+}
+
+impl ExpectedAnswers {
+    pub fn from_toml(s: &str) -> Result<Self> {
+        Ok(toml::from_str(s)?)
+    }
+
+    // Nothing is known when the input came from stdin or has no answers.toml beside it
+    pub fn for_input(input_path: Option<&Path>) -> Result<Self> {
+        let Some(dir) = input_path.and_then(Path::parent) else {
+            return Ok(Self::default());
+        };
+        let path = dir.join(ANSWERS_FILE);
+        match std::fs::read_to_string(&path) {
+            Ok(s) => {
+                Self::from_toml(&s).with_context(|| format!("Cannot parse {}", path.display()))
+            }
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err).with_context(|| format!("Cannot read {}", path.display())),
+        }
+    }
+
+    pub fn get(&self, part: u8) -> Option<&Answer> {
+        match part {
+            1 => self.part1.as_ref(),
+            2 => self.part2.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub fn verify(&self, part: u8, actual: &Answer) -> Verdict {
+        match self.get(part) {
+            Some(expected) if expected == actual => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected: expected.clone(),
+            },
+            None => Verdict::Unknown,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: Answer },
+    Unknown,
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "PASS"),
+            Verdict::Fail { expected } => write!(f, "FAIL, expected {expected}"),
+            Verdict::Unknown => write!(f, "UNKNOWN"),
+        }
+    }
+}
+
+// A day's answer to one part, checked against the recorded one
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Outcome {
+    pub day: u8,
+    pub part: u8,
+    pub answer: Answer,
+    pub verdict: Verdict,
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Day {} part {}: {} [{}]",
+            self.day, self.part, self.answer, self.verdict
+        )
+    }
+}
+
+pub fn ensure_no_failures(outcomes: &[Outcome]) -> Result<()> {
+    let failures = outcomes
+        .iter()
+        .filter(|o| matches!(o.verdict, Verdict::Fail { .. }))
+        .count();
+    if failures > 0 {
+        bail!("{failures} answer(s) did not match the recorded ones");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    const ANSWERS: &str = r#"
+        part1 = 74
+        part2 = "cqkaabcc"
+    "#;
+
+    #[test]
+    fn from_toml() {
+        let answers = ExpectedAnswers::from_toml(ANSWERS).unwrap();
+        assert_eq!(answers.get(1), Some(&Answer::Integer(74)));
+        assert_eq!(answers.get(2), Some(&Answer::from("cqkaabcc")));
+        assert_eq!(answers.get(3), None);
+    }
+
+    #[test]
+    fn from_toml_unknown_field() {
+        assert!(ExpectedAnswers::from_toml("part3 = 1").is_err());
+    }
+
+    #[test_case(1, Answer::Integer(74), Verdict::Pass; "pass")]
+    #[test_case(1, Answer::Integer(75), Verdict::Fail { expected: Answer::Integer(74) }; "fail")]
+    #[test_case(2, Answer::Integer(74), Verdict::Fail { expected: Answer::from("cqkaabcc") }; "fail on type")]
+    #[test_case(3, Answer::Integer(74), Verdict::Unknown; "unknown")]
+    fn verify(part: u8, actual: Answer, expected: Verdict) {
+        let answers = ExpectedAnswers::from_toml(ANSWERS).unwrap();
+        assert_eq!(answers.verify(part, &actual), expected);
+    }
+
+    #[test]
+    fn stdin_input_has_no_answers() {
+        let answers = ExpectedAnswers::for_input(None).unwrap();
+        assert_eq!(answers.verify(1, &Answer::Integer(1)), Verdict::Unknown);
+    }
+
+    #[test]
+    fn failures_are_errors() {
+        let outcome = |verdict| Outcome {
+            day: 1,
+            part: 1,
+            answer: Answer::Integer(74),
+            verdict,
+        };
+        assert!(ensure_no_failures(&[outcome(Verdict::Pass), outcome(Verdict::Unknown)]).is_ok());
+        let fail = Verdict::Fail {
+            expected: Answer::None,
+        };
+        assert!(ensure_no_failures(&[outcome(Verdict::Pass), outcome(fail)]).is_err());
+        assert_eq!(
+            outcome(Verdict::Pass).to_string(),
+            "Day 1 part 1: 74 [PASS]"
+        );
+    }
+}
@@ -0,0 +1,228 @@
+use crate::Solution;
+use anyhow::Result;
+use serde::Serialize;
+use std::fmt::Display;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Phase {
+    Parse,
+    Part1,
+    Part2,
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Phase::Parse => write!(f, "parse"),
+            Phase::Part1 => write!(f, "part1"),
+            Phase::Part2 => write!(f, "part2"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(mut samples: Vec<Duration>) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        samples.sort();
+        let mid = samples.len() / 2;
+        let median = if samples.len().is_multiple_of(2) {
+            (samples[mid - 1] + samples[mid]) / 2
+        } else {
+            samples[mid]
+        };
+        Some(Self {
+            min: samples[0],
+            median,
+            max: samples[samples.len() - 1],
+        })
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Measurement {
+    pub day: u8,
+    pub phase: Phase,
+    pub runs: usize,
+    pub min_ns: u64,
+    pub median_ns: u64,
+    pub max_ns: u64,
+}
+
+impl Measurement {
+    fn new(day: u8, phase: Phase, runs: usize, stats: Stats) -> Self {
+        Self {
+            day,
+            phase,
+            runs,
+            min_ns: stats.min.as_nanos() as u64,
+            median_ns: stats.median.as_nanos() as u64,
+            max_ns: stats.max.as_nanos() as u64,
+        }
+    }
+}
+
+impl Display for Measurement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Day {:<2} {:<5}  min {:>12?}  median {:>12?}  max {:>12?}  ({} runs)",
+            self.day,
+            self.phase,
+            Duration::from_nanos(self.min_ns),
+            Duration::from_nanos(self.median_ns),
+            Duration::from_nanos(self.max_ns),
+            self.runs
+        )
+    }
+}
+
+fn time_runs<F: FnMut() -> Result<()>>(runs: usize, mut f: F) -> Result<Stats> {
+    let mut samples = Vec::with_capacity(runs);
+    for _ in 0..runs {
+        let start = Instant::now();
+        f()?;
+        samples.push(start.elapsed());
+    }
+    Ok(Stats::from_samples(samples).unwrap())
+}
+
+pub fn bench_day(
+    day: u8,
+    solution: &dyn Solution,
+    input: &str,
+    runs: usize,
+) -> Result<Vec<Measurement>> {
+    let runs = runs.max(1);
+    let mut measurements = Vec::new();
+    if solution.parses() {
+        let parse = time_runs(runs, || solution.parse(input))?;
+        measurements.push(Measurement::new(day, Phase::Parse, runs, parse));
+    }
+    let part1 = time_runs(runs, || solution.part1(input).map(|_| ()))?;
+    measurements.push(Measurement::new(day, Phase::Part1, runs, part1));
+    let part2 = time_runs(runs, || solution.part2(input).map(|_| ()))?;
+    measurements.push(Measurement::new(day, Phase::Part2, runs, part2));
+    Ok(measurements)
+}
+
+pub fn to_json(measurements: &[Measurement]) -> Result<String> {
+    Ok(serde_json::to_string_pretty(measurements)?)
+}
+
+pub fn to_csv(measurements: &[Measurement]) -> String {
+    let mut csv = String::from("day,phase,runs,min_ns,median_ns,max_ns\n");
+    for m in measurements {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            m.day, m.phase, m.runs, m.min_ns, m.median_ns, m.max_ns
+        ));
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Answer;
+    use test_case::test_case;
+
+    struct Noop;
+
+    impl Solution for Noop {
+        fn part1(&self, _input: &str) -> Result<Answer> {
+            Ok(Answer::None)
+        }
+
+        fn part2(&self, _input: &str) -> Result<Answer> {
+            Ok(Answer::None)
+        }
+    }
+
+    struct Parsed;
+
+    impl Solution for Parsed {
+        fn parses(&self) -> bool {
+            true
+        }
+
+        fn parse(&self, _input: &str) -> Result<()> {
+            Ok(())
+        }
+
+        fn part1(&self, _input: &str) -> Result<Answer> {
+            Ok(Answer::None)
+        }
+
+        fn part2(&self, _input: &str) -> Result<Answer> {
+            Ok(Answer::None)
+        }
+    }
+
+    #[test_case(&[5], 5; "single")]
+    #[test_case(&[9, 1, 5], 5; "odd")]
+    #[test_case(&[8, 2, 4, 6], 5; "even")]
+    fn stats_from_samples(millis: &[u64], median: u64) {
+        let samples = millis.iter().map(|&ms| Duration::from_millis(ms)).collect();
+        let stats = Stats::from_samples(samples).unwrap();
+        assert_eq!(
+            stats.min,
+            Duration::from_millis(*millis.iter().min().unwrap())
+        );
+        assert_eq!(stats.median, Duration::from_millis(median));
+        assert_eq!(
+            stats.max,
+            Duration::from_millis(*millis.iter().max().unwrap())
+        );
+    }
+
+    #[test]
+    fn stats_without_samples() {
+        assert_eq!(Stats::from_samples(Vec::new()), None);
+    }
+
+    #[test]
+    fn bench_day_reports_every_phase() {
+        let measurements = bench_day(3, &Parsed, "", 4).unwrap();
+        let phases: Vec<Phase> = measurements.iter().map(|m| m.phase).collect();
+        assert_eq!(phases, vec![Phase::Parse, Phase::Part1, Phase::Part2]);
+        assert!(measurements.iter().all(|m| m.day == 3 && m.runs == 4));
+    }
+
+    #[test]
+    fn bench_day_without_parse() {
+        let measurements = bench_day(3, &Noop, "", 4).unwrap();
+        let phases: Vec<Phase> = measurements.iter().map(|m| m.phase).collect();
+        assert_eq!(phases, vec![Phase::Part1, Phase::Part2]);
+    }
+
+    #[test]
+    fn machine_readable_output() {
+        let measurements = vec![Measurement {
+            day: 4,
+            phase: Phase::Part2,
+            runs: 3,
+            min_ns: 10,
+            median_ns: 20,
+            max_ns: 30,
+        }];
+        assert_eq!(
+            to_csv(&measurements),
+            "day,phase,runs,min_ns,median_ns,max_ns\n4,part2,3,10,20,30\n"
+        );
+        let json: serde_json::Value =
+            serde_json::from_str(&to_json(&measurements).unwrap()).unwrap();
+        assert_eq!(json[0]["phase"], "part2");
+        assert_eq!(json[0]["median_ns"], 20);
+    }
+}
@@ -0,0 +1,241 @@
+use anyhow::Result;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use common::InputSource;
+use common::answers::{self, Outcome};
+use common::bench;
+use common::inputs::InputCache;
+use common::registry::Registry;
+use common::scaffold;
+use itertools::Itertools;
+use std::path::{Path, PathBuf};
+
+// Each day is compiled in from its own directory, its standalone `main` stays unused here
+#[allow(dead_code)]
+#[path = "../day1/main.rs"]
+mod day1;
+#[allow(dead_code)]
+#[path = "../day10/main.rs"]
+mod day10;
+#[allow(dead_code)]
+#[path = "../day11/main.rs"]
+mod day11;
+#[allow(dead_code)]
+#[path = "../day12/main.rs"]
+mod day12;
+#[allow(dead_code)]
+#[path = "../day13/main.rs"]
+mod day13;
+#[allow(dead_code)]
+#[path = "../day14/main.rs"]
+mod day14;
+#[allow(dead_code)]
+#[path = "../day2/main.rs"]
+mod day2;
+#[allow(dead_code)]
+#[path = "../day3/main.rs"]
+mod day3;
+#[allow(dead_code)]
+#[path = "../day4/main.rs"]
+mod day4;
+#[allow(dead_code)]
+#[path = "../day5/main.rs"]
+mod day5;
+#[allow(dead_code)]
+#[path = "../day6/main.rs"]
+mod day6;
+#[allow(dead_code)]
+#[path = "../day7/main.rs"]
+mod day7;
+#[allow(dead_code)]
+#[path = "../day8/main.rs"]
+mod day8;
+#[allow(dead_code)]
+#[path = "../day9/main.rs"]
+mod day9;
+
+#[derive(Parser)]
+#[command(name = "aoc", about = "Runs the Advent of Code 2015 solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List every registered day and its parts
+    List,
+    /// Run a single part, a whole day or every day
+    Run(RunArgs),
+    /// Time both parts of a day, and its parsing when done separately, over repeated runs
+    Bench(BenchArgs),
+    /// Download missing puzzle inputs into the local cache
+    Fetch(FetchArgs),
+    /// Generate the skeleton of a new day and register it with the runner
+    New(NewArgs),
+}
+
+#[derive(Args)]
+struct RunArgs {
+    /// Day to run
+    #[arg(short, long, required_unless_present = "all")]
+    day: Option<u8>,
+    /// Part to run (all parts of the day when omitted)
+    #[arg(short, long, requires = "day")]
+    part: Option<u8>,
+    /// Run every registered day
+    #[arg(short, long, conflicts_with_all = ["day", "part"])]
+    all: bool,
+    /// Input file to use instead of the day's input.txt, "-" reads stdin
+    #[arg(short, long, requires = "day")]
+    input: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct BenchArgs {
+    /// Day to benchmark
+    #[arg(short, long, required_unless_present = "all")]
+    day: Option<u8>,
+    /// Benchmark every registered day
+    #[arg(short, long, conflicts_with = "day")]
+    all: bool,
+    /// Number of timed runs for each phase
+    #[arg(short, long, default_value_t = 10)]
+    runs: usize,
+    /// Output format of the summary
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+    /// Input file to use instead of the day's input.txt, "-" reads stdin
+    #[arg(short, long, requires = "day")]
+    input: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct FetchArgs {
+    /// Day to fetch
+    #[arg(short, long, required_unless_present = "all")]
+    day: Option<u8>,
+    /// Fetch every registered day
+    #[arg(short, long, conflicts_with = "day")]
+    all: bool,
+}
+
+#[derive(Args)]
+struct NewArgs {
+    /// Day to create
+    #[arg(short, long)]
+    day: u8,
+    /// Name of the day's domain module
+    #[arg(short, long)]
+    module: String,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+fn registry() -> Registry {
+    let mut registry = Registry::new();
+    day1::register(&mut registry);
+    day2::register(&mut registry);
+    day3::register(&mut registry);
+    day4::register(&mut registry);
+    day5::register(&mut registry);
+    day6::register(&mut registry);
+    day7::register(&mut registry);
+    day8::register(&mut registry);
+    day9::register(&mut registry);
+    day10::register(&mut registry);
+    day11::register(&mut registry);
+    day12::register(&mut registry);
+    day13::register(&mut registry);
+    day14::register(&mut registry);
+    registry
+}
+
+// Prints the outcomes of each day as soon as it is done
+fn run(registry: &Registry, args: &RunArgs) -> Result<Vec<Outcome>> {
+    let source = InputSource::new(args.input.clone());
+    let days = match args.day {
+        Some(day) => vec![day],
+        None => registry.days(),
+    };
+    let mut outcomes = Vec::new();
+    for day in days {
+        let day_outcomes = match args.part {
+            Some(part) => vec![registry.run(day, part, &source)?],
+            None => registry.run_day(day, &source)?,
+        };
+        day_outcomes
+            .iter()
+            .for_each(|outcome| println!("{outcome}"));
+        outcomes.extend(day_outcomes);
+    }
+    Ok(outcomes)
+}
+
+fn bench(registry: &Registry, args: &BenchArgs) -> Result<()> {
+    let source = InputSource::new(args.input.clone());
+    let days = match args.day {
+        Some(day) => vec![day],
+        None => registry.days(),
+    };
+    let mut measurements = Vec::new();
+    for day in days {
+        let solution = registry.solution(day)?;
+        let input = source.read(day)?;
+        measurements.extend(bench::bench_day(day, solution, &input, args.runs)?);
+    }
+    match args.format {
+        Format::Text => measurements.iter().for_each(|m| println!("{m}")),
+        Format::Json => println!("{}", bench::to_json(&measurements)?),
+        Format::Csv => print!("{}", bench::to_csv(&measurements)),
+    }
+    Ok(())
+}
+
+fn fetch(registry: &Registry, args: &FetchArgs) -> Result<()> {
+    let cache = InputCache::from_env();
+    let days = match args.day {
+        Some(day) => vec![day],
+        None => registry.days(),
+    };
+    for day in days {
+        let status = if cache.is_cached(day) {
+            "already cached"
+        } else {
+            cache.get(day)?;
+            "fetched"
+        };
+        println!("Day {day}: {status} at {}", cache.path(day).display());
+    }
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let registry = registry();
+    match &cli.command {
+        Command::List => {
+            for day in registry.days() {
+                let parts = registry.parts(day).iter().join(", ");
+                println!("Day {day}: parts {parts}");
+            }
+        }
+        Command::Run(args) => {
+            let outcomes = run(&registry, args)?;
+            answers::ensure_no_failures(&outcomes)?;
+        }
+        Command::Bench(args) => bench(&registry, args)?,
+        Command::Fetch(args) => fetch(&registry, args)?,
+        Command::New(args) => {
+            let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+            for path in scaffold::create_day(root, args.day, &args.module)? {
+                println!("{}", path.display());
+            }
+        }
+    }
+    Ok(())
+}
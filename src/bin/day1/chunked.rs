@@ -0,0 +1,175 @@
+use super::floor;
+use anyhow::{Result, bail};
+use std::thread;
+
+// Below this size a chunk is not worth its own thread
+const MIN_CHUNK_LEN: usize = 1 << 16;
+
+// What the elevator does over one chunk of the directions, taken on its own from floor 0
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct ChunkSummary {
+    // Net floor change over the chunk
+    delta: i64,
+    // Lowest floor reached within the chunk
+    min_prefix: i64,
+    // Whether every byte is '(' or ')'
+    valid: bool,
+}
+
+impl ChunkSummary {
+    fn of(chunk: &[u8]) -> Self {
+        let mut floor = 0_i64;
+        let mut min_prefix = 0_i64;
+        let mut valid = true;
+        for &b in chunk {
+            floor += (b == b'(') as i64 - (b == b')') as i64;
+            valid &= b == b'(' || b == b')';
+            min_prefix = min_prefix.min(floor);
+        }
+        Self {
+            delta: floor,
+            min_prefix,
+            valid,
+        }
+    }
+}
+
+// The directions without surrounding whitespace, when they only use the puzzle's '(' and ')'
+// and can be counted here. Anything else is left to `floor` and its alphabets.
+pub fn plain_directions(input: &str) -> Option<&[u8]> {
+    let directions = input.trim().as_bytes();
+    directions
+        .iter()
+        .all(|&b| b == b'(' || b == b')')
+        .then_some(directions)
+}
+
+fn chunk_len(input_len: usize) -> usize {
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    input_len.div_ceil(threads).max(MIN_CHUNK_LEN)
+}
+
+// Summaries of consecutive chunks of `input`, each computed on its own thread
+fn summaries(input: &[u8], chunk_len: usize) -> Vec<ChunkSummary> {
+    thread::scope(|scope| {
+        let handles: Vec<_> = input
+            .chunks(chunk_len.max(1))
+            .map(|chunk| scope.spawn(|| ChunkSummary::of(chunk)))
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    })
+}
+
+// Same result as `floor::floor_number_from_string`, counting on every core. Errors are
+// rare, so they are left to the sequential version to report exactly the same way.
+pub fn floor_number(input: &[u8]) -> Result<i32> {
+    floor_number_with_chunks(input, chunk_len(input.len()))
+}
+
+fn floor_number_with_chunks(input: &[u8], chunk_len: usize) -> Result<i32> {
+    let summaries = summaries(input, chunk_len);
+    let floor: i64 = summaries.iter().map(|summary| summary.delta).sum();
+    match i32::try_from(floor) {
+        Ok(floor) if summaries.iter().all(|summary| summary.valid) => Ok(floor),
+        _ => floor::floor_number_from_reader(input),
+    }
+}
+
+// Same result as `floor::index_of_basement`. Only the chunk where the basement is
+// first reached is scanned again to find the exact position.
+pub fn index_of_basement(input: &[u8]) -> Result<i32> {
+    index_of_basement_with_chunks(input, chunk_len(input.len()))
+}
+
+fn index_of_basement_with_chunks(input: &[u8], chunk_len: usize) -> Result<i32> {
+    let chunk_len = chunk_len.max(1);
+    let summaries = summaries(input, chunk_len);
+    if !summaries.iter().all(|summary| summary.valid) {
+        // A bad character anywhere is an error, even past the basement
+        return floor::index_of_basement(&String::from_utf8_lossy(input));
+    }
+    let mut floor = 0_i64;
+    for (n, summary) in summaries.iter().enumerate() {
+        let start = n * chunk_len;
+        if floor + summary.min_prefix < 0 {
+            let chunk = &input[start..(start + chunk_len).min(input.len())];
+            let index = basement_in_chunk(chunk, floor);
+            return i32::try_from(start + index).or_else(|_| bail!("Basement index out of range"));
+        }
+        floor += summary.delta;
+    }
+    bail!("Never reached the basement!");
+}
+
+// 1-based index within `chunk` where the floor first drops below 0, entering it on `floor`
+fn basement_in_chunk(chunk: &[u8], mut floor: i64) -> usize {
+    for (i, &b) in chunk.iter().enumerate() {
+        floor += (b == b'(') as i64 - (b == b')') as i64;
+        if floor < 0 {
+            return i + 1;
+        }
+    }
+    unreachable!("the chunk summary said the basement is reached in this chunk")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::floor;
+    use proptest::prelude::*;
+    use test_case::test_case;
+
+    #[test_case("(()"; "t1")]
+    #[test_case(")())())"; "t2")]
+    #[test_case("((((()))))))"; "t3")]
+    #[test_case(""; "empty")]
+    #[test_case("(((x)))"; "bad character")]
+    #[test_case(")x"; "bad character after the basement")]
+    fn same_as_sequential(input: &str) {
+        let outcome = |result: anyhow::Result<i32>| result.map_err(|err| err.to_string());
+        for chunk_len in [1, 2, 3, 100] {
+            assert_eq!(
+                outcome(super::floor_number_with_chunks(input.as_bytes(), chunk_len)),
+                outcome(floor::floor_number_from_string(input))
+            );
+            assert_eq!(
+                outcome(super::index_of_basement_with_chunks(
+                    input.as_bytes(),
+                    chunk_len
+                )),
+                outcome(floor::index_of_basement(input))
+            );
+        }
+    }
+
+    #[test_case("(()\n", Some("(()"); "trailing newline")]
+    #[test_case("", Some(""); "empty")]
+    #[test_case("(( )", None; "inner whitespace")]
+    #[test_case("((x", None; "bad character")]
+    fn plain_directions(input: &str, expected: Option<&str>) {
+        assert_eq!(super::plain_directions(input), expected.map(str::as_bytes));
+    }
+
+    #[test]
+    fn large_input() {
+        let input = format!("{}{}", "(".repeat(300_000), ")".repeat(300_001));
+        assert_eq!(super::floor_number(input.as_bytes()).unwrap(), -1);
+        assert_eq!(super::index_of_basement(input.as_bytes()).unwrap(), 600_001);
+    }
+
+    proptest! {
+        #[test]
+        fn chunks_agree(input in "[()]{0,64}", chunk_len in 1..20_usize) {
+            prop_assert_eq!(
+                super::floor_number_with_chunks(input.as_bytes(), chunk_len).ok(),
+                floor::floor_number_from_string(&input).ok()
+            );
+            prop_assert_eq!(
+                super::index_of_basement_with_chunks(input.as_bytes(), chunk_len).ok(),
+                floor::index_of_basement(&input).ok()
+            );
+        }
+    }
+}
@@ -1,38 +1,259 @@
-use anyhow::{Result, bail};
-
-pub fn floor_number_from_string(s: &String) -> Result<i32> {
-    let mut floor = 0;
-    for ch in s.chars() {
-        floor += match ch {
-            '(' => 1,
-            ')' => -1,
-            other_ch => {
-                bail!("Bad character {other_ch}");
+use anyhow::{Context, Result};
+use common::alphabet::{Alphabet, Symbol};
+use common::stream::Chars;
+use std::collections::BTreeMap;
+use std::io::BufRead;
+use std::ops::{Range, RangeFrom, RangeInclusive, RangeTo, RangeToInclusive};
+use std::str::FromStr;
+
+// The puzzle's '(' up one floor and ')' down one floor. A move can be more than one floor,
+// the floors in between are then not visited.
+pub const DIRECTIONS: [(char, i32); 2] = [('(', 1), (')', -1)];
+
+// Which characters move the elevator and by how many floors
+pub type ElevatorAlphabet = Alphabet<i32>;
+
+// Follows the directions one character at a time, yielding (step, floor) after every move.
+// Ignored characters are skipped and do not count as steps.
+struct Walk<'a, R> {
+    chars: Chars<R>,
+    alphabet: &'a ElevatorAlphabet,
+    step: usize,
+    floor: i32,
+}
+
+impl<'a, R: BufRead> Walk<'a, R> {
+    fn new(reader: R, alphabet: &'a ElevatorAlphabet) -> Self {
+        Self {
+            chars: Chars::new(reader),
+            alphabet,
+            step: 0,
+            floor: 0,
+        }
+    }
+
+    fn next_step(&mut self) -> Result<Option<(usize, i32)>> {
+        while let Some(ch) = self.chars.next() {
+            let ch = ch?;
+            let delta = match self.alphabet.symbol(ch) {
+                Some(Symbol::Move(delta)) => delta,
+                Some(Symbol::Ignore) => continue,
+                None => return Err(self.chars.error(format!("Bad character {ch:?}")).into()),
+            };
+            self.floor = self
+                .floor
+                .checked_add(delta)
+                .ok_or_else(|| self.chars.error("Floor out of range"))?;
+            self.step += 1;
+            return Ok(Some((self.step, self.floor)));
+        }
+        Ok(None)
+    }
+}
+
+impl<R: BufRead> Iterator for Walk<'_, R> {
+    type Item = Result<(usize, i32)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_step().transpose()
+    }
+}
+
+// The whole trip of the elevator, walked once. Step 0 is the start on floor 0 and
+// step n is where Santa is after following the n-th direction.
+#[derive(Debug)]
+pub struct FloorTrace {
+    floors: Vec<i32>,
+    first_visits: BTreeMap<i32, usize>,
+    time_per_floor: BTreeMap<i32, usize>,
+    highest: (i32, usize),
+    lowest: (i32, usize),
+}
+
+impl FromStr for FloorTrace {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::from_reader(s.as_bytes())
+    }
+}
+
+impl FloorTrace {
+    pub fn from_reader(reader: impl BufRead) -> Result<Self> {
+        Self::from_reader_with(reader, &ElevatorAlphabet::new(DIRECTIONS))
+    }
+
+    pub fn from_reader_with(reader: impl BufRead, alphabet: &ElevatorAlphabet) -> Result<Self> {
+        let mut trace = Self {
+            floors: vec![0],
+            first_visits: BTreeMap::from([(0, 0)]),
+            time_per_floor: BTreeMap::from([(0, 1)]),
+            highest: (0, 0),
+            lowest: (0, 0),
+        };
+        for step in Walk::new(reader, alphabet) {
+            let (step, floor) = step?;
+            trace.floors.push(floor);
+            trace.first_visits.entry(floor).or_insert(step);
+            *trace.time_per_floor.entry(floor).or_insert(0) += 1;
+            if floor > trace.highest.0 {
+                trace.highest = (floor, step);
+            }
+            if floor < trace.lowest.0 {
+                trace.lowest = (floor, step);
             }
         }
+        Ok(trace)
+    }
+
+    // First step at which `floor` was reached, 0 for the ground floor
+    pub fn first_visit(&self, floor: i32) -> Option<usize> {
+        self.first_visits.get(&floor).copied()
     }
-    Ok(floor)
 }
 
-pub fn index_of_basement(s: &String) -> Result<i32> {
-    let mut floor = 0;
-    for (index, ch) in s.chars().enumerate() {
-        floor += match ch {
-            '(' => 1,
-            ')' => -1,
-            other_ch => {
-                bail!("Bad character {other_ch}");
+// Not every query on the elevator trip is needed by the two parts
+#[allow(dead_code)]
+impl FloorTrace {
+    // Number of directions followed
+    pub fn len(&self) -> usize {
+        self.floors.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn final_floor(&self) -> i32 {
+        self.floors[self.len()]
+    }
+
+    pub fn first_visits(&self) -> &BTreeMap<i32, usize> {
+        &self.first_visits
+    }
+
+    // Highest floor reached and the first step that got there
+    pub fn highest(&self) -> (i32, usize) {
+        self.highest
+    }
+
+    // Lowest floor reached and the first step that got there
+    pub fn lowest(&self) -> (i32, usize) {
+        self.lowest
+    }
+
+    // Every direction takes one unit of time, the start counts as one unit on floor 0
+    pub fn time_on(&self, floor: i32) -> usize {
+        self.time_per_floor.get(&floor).copied().unwrap_or(0)
+    }
+
+    pub fn time_per_floor(&self) -> &BTreeMap<i32, usize> {
+        &self.time_per_floor
+    }
+
+    // (step, floor) after every direction, starting from step 1
+    pub fn steps(&self) -> impl Iterator<Item = (usize, i32)> + '_ {
+        self.floors.iter().copied().enumerate().skip(1)
+    }
+
+    // Every step that ends on the target floor(s). The start is not a step, and a floor
+    // jumped over by a multi-floor move is not reached.
+    pub fn all_reaching<'a>(
+        &'a self,
+        target: impl FloorTarget + 'a,
+    ) -> impl Iterator<Item = usize> + 'a {
+        self.steps()
+            .filter(move |&(_, floor)| target.contains_floor(floor))
+            .map(|(step, _)| step)
+    }
+
+    pub fn first_reaching(&self, target: impl FloorTarget) -> Option<usize> {
+        self.all_reaching(target).next()
+    }
+
+    pub fn last_reaching(&self, target: impl FloorTarget) -> Option<usize> {
+        self.all_reaching(target).last()
+    }
+}
+
+// A single floor or a range of floors to search the trace for
+#[allow(dead_code)]
+pub trait FloorTarget {
+    fn contains_floor(&self, floor: i32) -> bool;
+}
+
+impl FloorTarget for i32 {
+    fn contains_floor(&self, floor: i32) -> bool {
+        *self == floor
+    }
+}
+
+macro_rules! floor_target_range {
+    ($($range:ty),*) => {
+        $(
+            impl FloorTarget for $range {
+                fn contains_floor(&self, floor: i32) -> bool {
+                    self.contains(&floor)
+                }
             }
-        };
+        )*
+    };
+}
+
+floor_target_range!(
+    Range<i32>,
+    RangeInclusive<i32>,
+    RangeFrom<i32>,
+    RangeTo<i32>,
+    RangeToInclusive<i32>
+);
+
+#[allow(dead_code)]
+pub fn floor_number_from_string(s: &str) -> Result<i32> {
+    Ok(s.parse::<FloorTrace>()?.final_floor())
+}
+
+// Reads the directions as a stream, memory use does not depend on the input size
+pub fn floor_number_from_reader(reader: impl BufRead) -> Result<i32> {
+    floor_number_from_reader_with(reader, &ElevatorAlphabet::new(DIRECTIONS))
+}
+
+pub fn floor_number_from_reader_with(
+    reader: impl BufRead,
+    alphabet: &ElevatorAlphabet,
+) -> Result<i32> {
+    Walk::new(reader, alphabet).try_fold(0, |_, step| step.map(|(_, floor)| floor))
+}
+
+pub fn index_of_basement(s: &str) -> Result<i32> {
+    let trace = s.parse::<FloorTrace>()?;
+    let index = trace
+        .first_visit(-1)
+        .context("Never reached the basement!")?;
+    i32::try_from(index).context("Basement index out of range")
+}
+
+#[allow(dead_code)]
+pub fn index_of_basement_from_reader(reader: impl BufRead) -> Result<i32> {
+    index_of_basement_from_reader_with(reader, &ElevatorAlphabet::new(DIRECTIONS))
+}
+
+pub fn index_of_basement_from_reader_with(
+    reader: impl BufRead,
+    alphabet: &ElevatorAlphabet,
+) -> Result<i32> {
+    for step in Walk::new(reader, alphabet) {
+        let (index, floor) = step?;
         if floor == -1 {
-            return Ok(index as i32 + 1);
+            return i32::try_from(index).context("Basement index out of range");
         }
     }
-    bail!("Never reached the basement!");
+    anyhow::bail!("Never reached the basement!");
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
     use test_case::test_case;
 
     #[test_case("(())", 0; "t1")]
@@ -46,13 +267,125 @@ mod tests {
     #[test_case(")())())", -3; "t9")]
 
     fn floor_number_from_string(direction: &str, expected: i32) {
-        let result = super::floor_number_from_string(&direction.to_string()).unwrap();
+        let result = super::floor_number_from_string(direction).unwrap();
         assert_eq!(result, expected);
     }
     #[test_case(")", 1; "t1")]
     #[test_case("()())", 5; "t2")]
     fn index_of_basement(directions: &str, expected: i32) {
-        let result = super::index_of_basement(&directions.into()).unwrap();
+        let result = super::index_of_basement(directions).unwrap();
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn from_reader() {
+        let reader = std::io::BufReader::with_capacity(2, "(()))(".as_bytes());
+        assert_eq!(super::floor_number_from_reader(reader).unwrap(), 0);
+        let reader = std::io::BufReader::with_capacity(2, "(()))(".as_bytes());
+        assert_eq!(super::index_of_basement_from_reader(reader).unwrap(), 5);
+    }
+
+    #[test]
+    fn trace() {
+        let trace = "(()))((((".parse::<super::FloorTrace>().unwrap();
+        assert_eq!(trace.len(), 9);
+        assert_eq!(trace.final_floor(), 3);
+        assert_eq!(trace.first_visit(-1), Some(5));
+        assert_eq!(trace.first_visit(3), Some(9));
+        assert_eq!(trace.first_visit(4), None);
+        assert_eq!(trace.highest(), (3, 9));
+        assert_eq!(trace.lowest(), (-1, 5));
+        assert_eq!(trace.time_on(0), 3);
+        assert_eq!(trace.time_on(1), 3);
+        assert_eq!(trace.time_per_floor().values().sum::<usize>(), 10);
+        let floors: Vec<i32> = trace.steps().map(|(_, floor)| floor).collect();
+        assert_eq!(floors, vec![1, 2, 1, 0, -1, 0, 1, 2, 3]);
+    }
+
+    #[test_case(1, Some(1), Some(7), 3; "single floor")]
+    #[test_case(4, None, None, 0; "never reached")]
+    #[test_case(-1, Some(5), Some(5), 1; "basement")]
+    fn search_floor(target: i32, first: Option<usize>, last: Option<usize>, count: usize) {
+        let trace = "(()))((((".parse::<super::FloorTrace>().unwrap();
+        assert_eq!(trace.first_reaching(target), first);
+        assert_eq!(trace.last_reaching(target), last);
+        assert_eq!(trace.all_reaching(target).count(), count);
+    }
+
+    #[test]
+    fn search_floor_range() {
+        let trace = "(()))((((".parse::<super::FloorTrace>().unwrap();
+        assert_eq!(trace.all_reaching(2..=3).collect::<Vec<_>>(), vec![2, 8, 9]);
+        assert_eq!(trace.first_reaching(..0), Some(5));
+        assert_eq!(trace.last_reaching(1..), Some(9));
+        assert_eq!(trace.first_reaching(10..20), None);
+    }
+
+    #[test]
+    fn empty_trace() {
+        let trace = "".parse::<super::FloorTrace>().unwrap();
+        assert!(trace.is_empty());
+        assert_eq!(trace.final_floor(), 0);
+        assert_eq!(trace.highest(), (0, 0));
+        assert_eq!(trace.steps().count(), 0);
+    }
+
+    #[test]
+    fn lenient_alphabet() {
+        assert!(super::floor_number_from_string("(()\n").is_err());
+        let alphabet = super::ElevatorAlphabet::lenient(super::DIRECTIONS);
+        let trace = super::FloorTrace::from_reader_with("(( )\n".as_bytes(), &alphabet).unwrap();
+        assert_eq!(trace.final_floor(), 1);
+        assert_eq!(trace.len(), 3);
+    }
+
+    #[test]
+    fn lenient_reader() {
+        let alphabet = super::ElevatorAlphabet::lenient(super::DIRECTIONS);
+        let reader = std::io::BufReader::with_capacity(2, "(( )))\n".as_bytes());
+        assert_eq!(
+            super::floor_number_from_reader_with(reader, &alphabet).unwrap(),
+            -1
+        );
+        let reader = std::io::BufReader::with_capacity(2, "(( )))\n".as_bytes());
+        assert_eq!(
+            super::index_of_basement_from_reader_with(reader, &alphabet).unwrap(),
+            5
+        );
+    }
+
+    #[test]
+    fn custom_alphabet() {
+        let alphabet = super::ElevatorAlphabet::new(super::DIRECTIONS)
+            .with_move('U', 10)
+            .with_move('D', -10)
+            .ignoring('.');
+        let trace = super::FloorTrace::from_reader_with("U.(D)D".as_bytes(), &alphabet).unwrap();
+        assert_eq!(trace.final_floor(), -10);
+        assert_eq!(trace.highest(), (11, 2));
+        assert_eq!(trace.first_visit(5), None);
+        assert!(super::FloorTrace::from_reader_with("U x".as_bytes(), &alphabet).is_err());
+    }
+
+    #[test]
+    fn floor_out_of_range() {
+        let alphabet = super::ElevatorAlphabet::new(super::DIRECTIONS).with_move('^', i32::MAX);
+        assert!(super::FloorTrace::from_reader_with("(^".as_bytes(), &alphabet).is_err());
+    }
+
+    #[test]
+    fn bad_character() {
+        let err = super::floor_number_from_string("(()x)").unwrap_err();
+        let err = err.downcast::<common::ParseError>().unwrap();
+        assert_eq!(err.line, Some(1));
+        assert_eq!(err.column(), 4);
+    }
+
+    proptest! {
+        #[test]
+        fn never_panics(s in "[()\n x]{0,40}|\\PC*") {
+            let _ = super::floor_number_from_string(&s);
+            let _ = super::index_of_basement(&s);
+        }
+    }
 }
@@ -1,10 +1,30 @@
+mod chunked;
 mod floor;
 mod part1;
 mod part2;
 
 use anyhow::Result;
+use common::registry::{self, Registry};
+use common::{Answer, Solution};
+
+// Not a streaming day: plain directions are counted on every core, which needs them all
+// in memory, see `chunked`
+pub struct Day1;
+
+impl Solution for Day1 {
+    fn part1(&self, input: &str) -> Result<Answer> {
+        part1::solve(input)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        part2::solve(input)
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(1, Day1);
+}
+
 fn main() -> Result<()> {
-    part1::run()?;
-    part2::run()?;
-    Ok(())
+    registry::main_for(register)
 }
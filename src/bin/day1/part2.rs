@@ -1,9 +1,16 @@
-use crate::floor;
+use super::chunked;
+use super::floor::{self, DIRECTIONS, ElevatorAlphabet};
 use anyhow::Result;
+use common::Answer;
 
-pub fn run() -> Result<()> {
-    let input = common::read_input(1)?;
-    let index = floor::index_of_basement(&input)?;
-    println!("Index of basement = {index}");
-    Ok(())
+pub fn solve(input: &str) -> Result<Answer> {
+    if let Some(directions) = chunked::plain_directions(input) {
+        return Ok(chunked::index_of_basement(directions)?.into());
+    }
+    // Anything else is walked one character at a time, keeping only the current floor
+    let index = floor::index_of_basement_from_reader_with(
+        input.as_bytes(),
+        &ElevatorAlphabet::lenient(DIRECTIONS),
+    )?;
+    Ok(index.into())
 }
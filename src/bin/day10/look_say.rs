@@ -1,16 +1,29 @@
-pub fn after_iters(start: &str, n: i32) -> String {
-    let mut start = String::from(start);
+use common::ParseError;
+
+pub fn after_iters(start: &str, n: i32) -> Result<String, ParseError> {
+    let digits = start.trim();
+    if digits.is_empty() {
+        return Err(ParseError::at_end(start, "Expected a sequence of digits"));
+    }
+    if let Some((i, ch)) = digits.char_indices().find(|(_, ch)| !ch.is_ascii_digit()) {
+        return Err(ParseError::at(
+            start,
+            &digits[i..i + ch.len_utf8()],
+            format!("Expected a digit, found {ch:?}"),
+        ));
+    }
+    let mut start = String::from(digits);
     for _ in 0..n {
         let end = read_aloud(&start);
         start = end;
     }
-    start
+    Ok(start)
 }
 
 // Helper function
-fn read_aloud(s: &String) -> String {
+fn read_aloud(s: &str) -> String {
     let mut current: Option<char> = None;
-    let mut count = 0;
+    let mut count = 0_usize;
     let mut vu8 = Vec::<u8>::new();
     for ch in s.chars() {
         match current {
@@ -18,7 +31,7 @@ fn read_aloud(s: &String) -> String {
                 if cur_ch == ch {
                     count += 1;
                 } else {
-                    vu8.push(count);
+                    push_count(&mut vu8, count);
                     vu8.push((cur_ch as u8) - 0x30);
                     count = 1;
                     current = Some(ch);
@@ -30,18 +43,44 @@ fn read_aloud(s: &String) -> String {
             }
         }
     }
-    vu8.push(count);
-    vu8.push((current.unwrap() as u8) - 0x30);
+    if let Some(cur_ch) = current {
+        push_count(&mut vu8, count);
+        vu8.push((cur_ch as u8) - 0x30);
+    }
     vu8.iter().map(|&d| (d + b'0') as char).collect()
 }
 
+// Runs of ten or more equal digits are read aloud with every digit of their length
+fn push_count(vu8: &mut Vec<u8>, count: usize) {
+    vu8.extend(count.to_string().bytes().map(|b| b - b'0'));
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_after_iters() {
-        let result = after_iters("1", 5);
+        let result = after_iters("1", 5).unwrap();
         assert_eq!(result, String::from("312211"));
     }
+
+    #[test]
+    fn long_runs() {
+        assert_eq!(read_aloud(&"1".repeat(12)), "121");
+    }
+
+    #[test]
+    fn bad_start() {
+        assert!(after_iters("", 1).is_err());
+        assert_eq!(after_iters("12a4", 1).unwrap_err().span, 2..3);
+    }
+
+    proptest! {
+        #[test]
+        fn never_panics(s in "[0-9]{0,30}|\\PC*") {
+            let _ = after_iters(&s, 2);
+        }
+    }
 }
@@ -1,10 +1,27 @@
 mod look_say;
 mod part1;
 mod part2;
+
 use anyhow::Result;
+use common::registry::{self, Registry};
+use common::{Answer, Solution};
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    fn part1(&self, input: &str) -> Result<Answer> {
+        part1::solve(input)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        part2::solve(input)
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(10, Day10);
+}
 
 fn main() -> Result<()> {
-    part1::run()?;
-    part2::run()?;
-    Ok(())
+    registry::main_for(register)
 }
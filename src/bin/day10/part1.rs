@@ -1,9 +1,8 @@
-use crate::look_say::after_iters;
+use super::look_say::after_iters;
 use anyhow::Result;
+use common::Answer;
 
-pub fn run() -> Result<()> {
-    let input = common::read_input(10)?;
-    let result = after_iters(input.as_str(), 40);
-    println!("Result after 40 times has length: {}", result.len());
-    Ok(())
+pub fn solve(input: &str) -> Result<Answer> {
+    let result = after_iters(input, 40)?;
+    Ok(result.len().into())
 }
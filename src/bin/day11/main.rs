@@ -1,8 +1,26 @@
 mod part1_2;
 mod password;
+
 use anyhow::Result;
+use common::registry::{self, Registry};
+use common::{Answer, Solution};
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    fn part1(&self, input: &str) -> Result<Answer> {
+        part1_2::solve_part1(input)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        part1_2::solve_part2(input)
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(11, Day11);
+}
 
 fn main() -> Result<()> {
-    part1_2::run()?;
-    Ok(())
+    registry::main_for(register)
 }
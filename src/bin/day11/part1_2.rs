@@ -1,10 +1,14 @@
-use crate::password::Password;
+use super::password::Password;
 use anyhow::Result;
+use common::Answer;
 
-pub fn run() -> Result<()> {
-    let input = common::read_input(11)?;
-    let mut pw = Password::from_str(&input);
-    println!("Next valid password: {}", pw.next_valid_password());
-    println!("Next valid password: {}", pw.next_valid_password());
-    Ok(())
+pub fn solve_part1(input: &str) -> Result<Answer> {
+    let mut pw = Password::from_str(input)?;
+    Ok(pw.next_valid_password().into())
+}
+
+pub fn solve_part2(input: &str) -> Result<Answer> {
+    let mut pw = Password::from_str(input)?;
+    pw.next_valid_password();
+    Ok(pw.next_valid_password().into())
 }
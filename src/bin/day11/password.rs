@@ -1,5 +1,8 @@
+use common::ParseError;
 use std::{collections::HashSet, io::Read};
 
+const PASSWORD_LEN: usize = 8;
+
 pub struct Password {
     current: Vec<u8>,
     bad_chars: Vec<u8>,
@@ -9,11 +12,29 @@ impl Password {
     //
     // Public Functions
     //
-    pub fn from_str(s: &str) -> Self {
-        Self {
-            current: s.as_bytes().to_vec(),
-            bad_chars: vec![b'i', b'o', b'l'],
+    pub fn from_str(s: &str) -> Result<Self, ParseError> {
+        let password = s.trim();
+        if let Some((i, ch)) = password
+            .char_indices()
+            .find(|(_, ch)| !ch.is_ascii_lowercase())
+        {
+            return Err(ParseError::at(
+                s,
+                &password[i..i + ch.len_utf8()],
+                format!("Expected a lowercase letter, found {ch:?}"),
+            ));
+        }
+        if password.len() != PASSWORD_LEN {
+            return Err(ParseError::at(
+                s,
+                password,
+                format!("Expected {PASSWORD_LEN} letters, found {}", password.len()),
+            ));
         }
+        Ok(Self {
+            current: password.as_bytes().to_vec(),
+            bad_chars: vec![b'i', b'o', b'l'],
+        })
     }
 
     pub fn password(&self) -> String {
@@ -38,8 +59,8 @@ impl Password {
     }
 
     fn base26_to_password(&mut self, mut n: u64) {
-        let mut chars: Vec<u8> = vec![b'a'; 8];
-        for i in (0..8).rev() {
+        let mut chars: Vec<u8> = vec![b'a'; PASSWORD_LEN];
+        for i in (0..PASSWORD_LEN).rev() {
             chars[i] = ((n % 26) + b'a' as u64) as u8;
             n /= 26;
         }
@@ -86,7 +107,7 @@ impl Password {
     }
 
     fn is_valid_password(&self) -> bool {
-        self.current.len() == 8
+        self.current.len() == PASSWORD_LEN
             && self.has_no_bad_letters()
             && self.has_consecutive_triplet()
             && self.has_two_pairs()
@@ -96,27 +117,28 @@ impl Password {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
     use test_case::test_case;
 
     #[test_case("asdfbnyp", true; "tc1")]
     #[test_case("asdibnyp", false; "tc2")]
     #[test_case("asdzbnyo", false; "tc3")]
     fn has_no_bad_letters(password: &str, expected: bool) {
-        let pw = Password::from_str(password);
+        let pw = Password::from_str(password).unwrap();
         assert_eq!(pw.has_no_bad_letters(), expected);
     }
 
     #[test_case("zotabcyy", true; "tc1")]
     #[test_case("asdibnyp", false; "tc2")]
     fn has_consecutive_triplet(password: &str, expected: bool) {
-        let pw = Password::from_str(password);
+        let pw = Password::from_str(password).unwrap();
         assert_eq!(pw.has_consecutive_triplet(), expected);
     }
 
     #[test_case("zotabcyy", true; "tc1")]
     #[test_case("aadibbyp", false; "tc2")]
     fn has_two_pairs(password: &str, expected: bool) {
-        let pw = Password::from_str(password);
+        let pw = Password::from_str(password).unwrap();
         assert_eq!(pw.has_consecutive_triplet(), expected);
     }
 
@@ -124,14 +146,14 @@ mod tests {
     #[test_case("abbceffg", false; "tc2")]
     #[test_case("abbcegjk", false; "tc3")]
     fn valid_password(password: &str, expected: bool) {
-        let pw = Password::from_str(password);
+        let pw = Password::from_str(password).unwrap();
         assert_eq!(pw.is_valid_password(), expected);
     }
 
     #[test_case("asdfbnyp", "asdfbnyq"; "tc1")]
     #[test_case("abcdefgz", "abcdefha"; "tc2")]
     fn increase(password: &str, expected: &str) {
-        let mut pw = Password::from_str(password);
+        let mut pw = Password::from_str(password).unwrap();
         pw.increase();
         assert_eq!(pw.password(), expected);
     }
@@ -139,7 +161,23 @@ mod tests {
     #[test_case("abcdefgh", "abcdffaa"; "tc1")]
     #[test_case("ghijklmn", "ghjaabcc"; "tc2")]
     fn next_valid_password(password: &str, expected: &str) {
-        let mut pw = Password::from_str(password);
+        let mut pw = Password::from_str(password).unwrap();
         assert_eq!(pw.next_valid_password(), expected);
     }
+
+    #[test_case("abcdefg"; "too short")]
+    #[test_case("abcdefghi"; "too long")]
+    #[test_case("abcDefgh"; "not lowercase")]
+    fn bad_password(password: &str) {
+        assert!(Password::from_str(password).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn from_str_never_panics(s in "[a-z]{0,10}|\\PC*") {
+            if let Ok(mut pw) = Password::from_str(&s) {
+                pw.increase();
+            }
+        }
+    }
 }
@@ -1,7 +1,34 @@
 mod part1_2;
+
 use anyhow::Result;
+use common::registry::{self, Registry};
+use common::{Answer, Solution};
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    fn parses(&self) -> bool {
+        true
+    }
+
+    fn parse(&self, input: &str) -> Result<()> {
+        part1_2::parse_json(input)?;
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        part1_2::solve_part1(input)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        part1_2::solve_part2(input)
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(12, Day12);
+}
 
 fn main() -> Result<()> {
-    part1_2::run()?;
-    Ok(())
+    registry::main_for(register)
 }
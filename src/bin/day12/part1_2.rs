@@ -1,42 +1,68 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use common::{Answer, ParseError};
 use serde_json::Value;
 
 trait Sum {
-    fn sum(&self, ignore_red: bool) -> i64;
+    fn sum(&self, ignore_red: bool) -> Result<i64>;
+}
+
+// Adds up `values`, failing rather than wrapping when the total leaves the i64 range
+fn checked_sum<'a>(mut values: impl Iterator<Item = &'a Value>, ignore_red: bool) -> Result<i64> {
+    values.try_fold(0_i64, |total, value| {
+        total
+            .checked_add(value.sum(ignore_red)?)
+            .context("Sum of the numbers out of range")
+    })
 }
 
 impl Sum for Value {
-    fn sum(&self, ignore_red: bool) -> i64 {
-        match self {
+    fn sum(&self, ignore_red: bool) -> Result<i64> {
+        Ok(match self {
             Value::Null => 0,
             Value::Bool(_) => 0,
-            Value::Number(v) => v.as_i64().unwrap_or(0),
+            Value::Number(v) => v
+                .as_i64()
+                .with_context(|| format!("Number {v} is not an integer that fits in an i64"))?,
             Value::String(_) => 0,
-            Value::Array(v) => v.iter().map(|e| e.sum(ignore_red)).sum(),
+            Value::Array(v) => checked_sum(v.iter(), ignore_red)?,
             Value::Object(m) => {
                 let red_keys = m.contains_key("red");
                 let red_values = m.values().any(|v| v == "red");
                 if ignore_red && (red_keys || red_values) {
                     0
                 } else {
-                    m.values().map(|v| v.sum(ignore_red)).sum()
+                    checked_sum(m.values(), ignore_red)?
                 }
             }
-        }
+        })
     }
 }
 
-pub fn run() -> Result<()> {
-    let input = common::read_input(12)?;
-    let v: Value = serde_json::from_str(&input)?;
-    println!("Sum: {}", v.sum(false));
-    println!("Sum without red: {}", v.sum(true));
-    Ok(())
+// serde_json reports where the document went wrong, turn that into a ParseError
+pub fn parse_json(input: &str) -> Result<Value, ParseError> {
+    serde_json::from_str(input).map_err(|err| {
+        let message = err.to_string();
+        let message = message
+            .rsplit_once(" at line ")
+            .map_or(message.as_str(), |(message, _)| message);
+        ParseError::at_line_column(input, err.line(), err.column(), message)
+    })
+}
+
+pub fn solve_part1(input: &str) -> Result<Answer> {
+    let v = parse_json(input)?;
+    Ok(v.sum(false)?.into())
+}
+
+pub fn solve_part2(input: &str) -> Result<Answer> {
+    let v = parse_json(input)?;
+    Ok(v.sum(true)?.into())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
     use test_case::test_case;
 
     //
@@ -59,6 +85,30 @@ mod tests {
     #[test_case(r#"[1,"red",5]"#, true, 6; "p2_4")]
     fn test_name(input: &str, ignore_red: bool, expected: i64) {
         let v: Value = serde_json::from_str(input).unwrap();
-        assert_eq!(v.sum(ignore_red), expected);
+        assert_eq!(v.sum(ignore_red).unwrap(), expected);
+    }
+
+    #[test_case("[9223372036854775807, 1]"; "overflow")]
+    #[test_case("[-9223372036854775808, -1]"; "underflow")]
+    #[test_case(r#"{"a": [18446744073709551615]}"#; "too large")]
+    #[test_case("[1.5]"; "not an integer")]
+    fn sum_out_of_range(input: &str) {
+        let v: Value = serde_json::from_str(input).unwrap();
+        assert!(v.sum(false).is_err());
+    }
+
+    #[test]
+    fn parse_json_error() {
+        let err = parse_json("[1,\n 2,}").unwrap_err();
+        assert_eq!(err.line, Some(2));
+        assert_eq!(err.column(), 4);
+        assert!(!err.message.contains("at line"));
+    }
+
+    proptest! {
+        #[test]
+        fn parse_json_never_panics(s in r#"[\[\]{}:,"a-z0-9\n -]{0,20}|\PC*"#) {
+            let _ = parse_json(&s);
+        }
     }
 }
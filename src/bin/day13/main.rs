@@ -1,10 +1,35 @@
 mod part1;
 mod part2;
 mod round_table;
+
 use anyhow::Result;
+use common::registry::{self, Registry};
+use common::{Answer, Solution};
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    fn parses(&self) -> bool {
+        true
+    }
+
+    fn parse(&self, input: &str) -> Result<()> {
+        round_table::RoundTable::from_input(input).map(|_| ())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        part1::solve(input)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        part2::solve(input)
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(13, Day13);
+}
 
 fn main() -> Result<()> {
-    part1::run()?;
-    part2::run()?;
-    Ok(())
+    registry::main_for(register)
 }
@@ -1,10 +1,9 @@
-use crate::round_table::RoundTable;
+use super::round_table::RoundTable;
 use anyhow::Result;
+use common::Answer;
 
-pub fn run() -> Result<()> {
-    let input = common::read_input(13)?;
-    let rt = RoundTable::from_input(&input.to_string())?;
+pub fn solve(input: &str) -> Result<Answer> {
+    let rt = RoundTable::from_input(input)?;
     let max_points = rt.get_max_happiness()?;
-    println!("The best disposition has {max_points} points");
-    Ok(())
+    Ok(max_points.into())
 }
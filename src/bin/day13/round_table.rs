@@ -1,4 +1,6 @@
-use anyhow::{Result, bail};
+use anyhow::{Context, Result, bail};
+use common::ParseError;
+use common::parse_error::parse_lines;
 use itertools::Itertools;
 
 const MYSELF: &str = "Myself";
@@ -11,28 +13,45 @@ pub struct Happiness {
 }
 
 impl Happiness {
-    pub fn from_str(s: &str) -> Result<Self> {
-        let mut parts = s
-            .trim()
-            .split_ascii_whitespace()
-            .map(|s| s.to_string())
-            .collect::<Vec<String>>();
+    pub fn from_str(s: &str) -> Result<Self, ParseError> {
+        let parts = s.split_ascii_whitespace().collect::<Vec<&str>>();
         if parts.len() != 11 {
-            bail!("Found {} parts instead of 11 in '{}'", parts.len(), s);
+            return Err(ParseError::at(
+                s,
+                s.trim(),
+                format!("Found {} parts instead of 11", parts.len()),
+            ));
         }
-        let mut points = parts[3].parse::<i32>()?;
-        points *= match parts[2].as_str() {
-            "gain" => 1,
-            "lose" => -1,
+        // The sign comes from gain or lose, so that negating never overflows
+        let points = match parts[3].parse::<i32>() {
+            Ok(n) if n >= 0 => n,
+            _ => {
+                return Err(ParseError::at(
+                    s,
+                    parts[3],
+                    format!(
+                        "Expected a non-negative number of points, found '{}'",
+                        parts[3]
+                    ),
+                ));
+            }
+        };
+        let points = match parts[2] {
+            "gain" => points,
+            "lose" => -points,
             other => {
-                bail!("Expected 'gain' or 'lose', found '{other}' instead.");
+                return Err(ParseError::at(
+                    s,
+                    other,
+                    format!("Expected 'gain' or 'lose', found '{other}' instead."),
+                ));
             }
         };
-        // Remove trailin 'dot' from next
-        parts[10].pop();
+        // Remove trailing 'dot' from next
+        let next_to = parts[10].strip_suffix('.').unwrap_or(parts[10]);
         Ok(Self {
-            person: parts[0].clone(),
-            next_to: parts[10].clone(),
+            person: parts[0].to_string(),
+            next_to: next_to.to_string(),
             points,
         })
     }
@@ -45,17 +64,13 @@ pub struct RoundTable {
 }
 
 impl RoundTable {
-    pub fn from_input(input: &String) -> Result<Self> {
+    pub fn from_input(input: &str) -> Result<Self> {
         let mut rt = Self {
             attendees: Vec::new(),
             happiness_list: Vec::new(),
         };
 
-        for line in input.lines() {
-            if line.trim().is_empty() {
-                continue;
-            }
-            let h = Happiness::from_str(line)?;
+        for h in parse_lines(input, Happiness::from_str)? {
             rt.happiness_list.push(h.clone());
             if !rt.attendees.contains(&h.person) {
                 rt.attendees.push(h.person.clone());
@@ -88,21 +103,31 @@ impl RoundTable {
         None
     }
 
+    // Sum of the happiness of everyone at the table, an error rather than a wrong total on overflow
+    fn disposition_points(&self, disp: &[String]) -> Result<i32> {
+        let imax = disp.len();
+        let mut disp_points = 0_i32;
+        for i in 0..imax {
+            let person_left = if i > 0 { &disp[i - 1] } else { &disp[imax - 1] };
+            let person_right = if i < imax - 1 { &disp[i + 1] } else { &disp[0] };
+            let (Some(left_points), Some(right_points)) = (
+                self.happiness_of(&disp[i], person_left),
+                self.happiness_of(&disp[i], person_right),
+            ) else {
+                bail!("Bad table disposition: {disp:?}");
+            };
+            disp_points = disp_points
+                .checked_add(left_points)
+                .and_then(|points| points.checked_add(right_points))
+                .with_context(|| format!("Happiness out of range for {disp:?}"))?;
+        }
+        Ok(disp_points)
+    }
+
     pub fn get_max_happiness(&self) -> Result<i32> {
         let mut max_points = 0_i32;
         for disp in self.dispositions() {
-            let imax = disp.len();
-            let mut disp_points = 0_i32;
-            for i in 0..imax {
-                let person_left = if i > 0 { &disp[i - 1] } else { &disp[imax - 1] };
-                let person_right = if i < imax - 1 { &disp[i + 1] } else { &disp[0] };
-                let left_points = self.happiness_of(&disp[i], person_left);
-                let right_points = self.happiness_of(&disp[i], person_right);
-                if left_points.is_none() || right_points.is_none() {
-                    bail!("Bad table disposition: {disp:?}");
-                }
-                disp_points += left_points.unwrap() + right_points.unwrap();
-            }
+            let disp_points = self.disposition_points(&disp)?;
             if disp_points > max_points {
                 max_points = disp_points;
             }
@@ -114,18 +139,7 @@ impl RoundTable {
         self.attendees.push(MYSELF.to_string());
         let mut max_points = 0_i32;
         for disp in self.dispositions() {
-            let imax = disp.len();
-            let mut disp_points = 0_i32;
-            for i in 0..imax {
-                let person_left = if i > 0 { &disp[i - 1] } else { &disp[imax - 1] };
-                let person_right = if i < imax - 1 { &disp[i + 1] } else { &disp[0] };
-                let left_points = self.happiness_of(&disp[i], person_left);
-                let right_points = self.happiness_of(&disp[i], person_right);
-                if left_points.is_none() || right_points.is_none() {
-                    bail!("Bad table disposition: {disp:?}");
-                }
-                disp_points += left_points.unwrap() + right_points.unwrap();
-            }
+            let disp_points = self.disposition_points(&disp)?;
             if disp_points > max_points {
                 max_points = disp_points;
             }
@@ -138,6 +152,8 @@ impl RoundTable {
 mod tests {
 
     use super::*;
+    use proptest::prelude::*;
+    use test_case::test_case;
 
     #[test]
     fn test_get_max_happiness() {
@@ -155,8 +171,46 @@ mod tests {
             David would lose 7 happiness units by sitting next to Bob.
             David would gain 41 happiness units by sitting next to Carol.
         "#;
-        let rt = RoundTable::from_input(&input.to_string()).unwrap();
+        let rt = RoundTable::from_input(input).unwrap();
         let max_points = rt.get_max_happiness().unwrap();
         assert_eq!(max_points, 330);
     }
+
+    #[test]
+    fn bad_happiness() {
+        let input = "Alice would gain 54 happiness units by sitting next to Bob.\nBob would win 83 happiness units by sitting next to Alice.";
+        let err = RoundTable::from_input(input).unwrap_err();
+        let err = err.downcast::<ParseError>().unwrap();
+        assert_eq!(err.line, Some(2));
+        assert_eq!(err.span, 10..13);
+    }
+
+    #[test_case("Alice would lose -2147483648 happiness units by sitting next to Bob."; "most negative points")]
+    #[test_case("Alice would gain -5 happiness units by sitting next to Bob."; "signed points")]
+    #[test_case("Alice would lose 2147483648 happiness units by sitting next to Bob."; "too many points")]
+    fn bad_points(line: &str) {
+        let err = Happiness::from_str(line).unwrap_err();
+        assert_eq!(&line[err.span], line.split_whitespace().nth(3).unwrap());
+    }
+
+    #[test]
+    fn most_points_lost() {
+        let line = "Alice would lose 2147483647 happiness units by sitting next to Bob.";
+        assert_eq!(Happiness::from_str(line).unwrap().points, -i32::MAX);
+    }
+
+    #[test]
+    fn happiness_overflow() {
+        let input = "Alice would gain 2147483647 happiness units by sitting next to Bob.\nBob would gain 2147483647 happiness units by sitting next to Alice.";
+        let mut rt = RoundTable::from_input(input).unwrap();
+        assert!(rt.get_max_happiness().is_err());
+        assert!(rt.get_max_happiness_with_me().is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn from_str_never_panics(s in "[A-Za-z]{0,5} would (gain|lose|win) [0-9-]{0,12} happiness units by sitting next to [A-Za-z.]{0,6}|\\PC*") {
+            let _ = Happiness::from_str(&s);
+        }
+    }
 }
@@ -1,10 +1,35 @@
 mod part1;
 mod part2;
 mod reindeer;
+
 use anyhow::Result;
+use common::registry::{self, Registry};
+use common::{Answer, Solution};
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    fn parses(&self) -> bool {
+        true
+    }
+
+    fn parse(&self, input: &str) -> Result<()> {
+        reindeer::ReindeerList::from_str(input).map(|_| ())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        part1::solve(input)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        part2::solve(input)
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(14, Day14);
+}
 
 fn main() -> Result<()> {
-    part1::run()?;
-    part2::run()?;
-    Ok(())
+    registry::main_for(register)
 }
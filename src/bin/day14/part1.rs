@@ -1,11 +1,10 @@
-use crate::reindeer::ReindeerList;
+use super::reindeer::ReindeerList;
 use anyhow::Result;
+use common::Answer;
 
-pub fn run() -> Result<()> {
-    let input = common::read_input(14)?;
-    let rdv = ReindeerList::from_str(&input)?;
+pub fn solve(input: &str) -> Result<Answer> {
+    let rdv = ReindeerList::from_str(input)?;
     let secs = 2503;
-    let wdist = rdv.winning_distance_after(secs);
-    println!("The winning distance after {secs}s is: {wdist}");
-    Ok(())
+    let wdist = rdv.winning_distance_after(secs)?;
+    Ok(wdist.into())
 }
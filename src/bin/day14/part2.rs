@@ -1,11 +1,10 @@
-use crate::reindeer::ReindeerList;
+use super::reindeer::ReindeerList;
 use anyhow::Result;
+use common::Answer;
 
-pub fn run() -> Result<()> {
-    let input = common::read_input(14)?;
-    let mut rdv = ReindeerList::from_str(&input)?;
+pub fn solve(input: &str) -> Result<Answer> {
+    let mut rdv = ReindeerList::from_str(input)?;
     let secs = 2503;
-    let points = rdv.max_winning_points_after(secs);
-    println!("The max points after {secs}s is: {points}");
-    Ok(())
+    let points = rdv.max_winning_points_after(secs)?;
+    Ok(points.into())
 }
@@ -1,9 +1,11 @@
-use anyhow::{Result, bail};
+use anyhow::{Context, Result};
+use common::ParseError;
+use common::parse_error::parse_lines;
 use std::cmp::{max, min};
+use std::num::IntErrorKind;
 
 #[derive(Debug)]
 pub struct Reindeer {
-    #[allow(dead_code)]
     name: String,
     speed: i32,
     fly_time: i32,
@@ -13,20 +15,47 @@ pub struct Reindeer {
 }
 
 impl Reindeer {
-    pub fn from_str(s: &str) -> Result<Self> {
-        let parts = s
-            .trim()
-            .split_ascii_whitespace()
-            .map(|s| s.to_string())
-            .collect::<Vec<String>>();
+    pub fn from_str(s: &str) -> Result<Self, ParseError> {
+        let parts = s.split_ascii_whitespace().collect::<Vec<&str>>();
         if parts.len() != 15 {
-            bail!("Found {} parts instead of 15 in '{}'", parts.len(), s);
+            return Err(ParseError::at(
+                s,
+                s.trim(),
+                format!("Found {} parts instead of 15", parts.len()),
+            ));
+        }
+        let number = |part: &str| match part.parse::<i32>() {
+            Ok(n) if n >= 0 => Ok(n),
+            Err(err) if *err.kind() == IntErrorKind::PosOverflow => Err(ParseError::at(
+                s,
+                part,
+                format!(
+                    "Number '{part}' is too large, at most {} is allowed",
+                    i32::MAX
+                ),
+            )),
+            _ => Err(ParseError::at(
+                s,
+                part,
+                format!("Expected a non-negative number, found '{part}'"),
+            )),
+        };
+        let speed = number(parts[3])?;
+        let fly_time = number(parts[6])?;
+        let rest_time = number(parts[13])?;
+        // distance_after() divides by the length of a fly and rest cycle
+        if fly_time
+            .checked_add(rest_time)
+            .is_none_or(|cycle| cycle == 0)
+        {
+            return Err(ParseError::at(
+                s,
+                s.trim(),
+                format!("Cannot fly {fly_time} and rest {rest_time} seconds"),
+            ));
         }
-        let speed = parts[3].parse::<i32>()?;
-        let fly_time = parts[6].parse::<i32>()?;
-        let rest_time = parts[13].parse::<i32>()?;
         Ok(Self {
-            name: parts[0].clone(),
+            name: parts[0].to_string(),
             speed,
             fly_time,
             rest_time,
@@ -37,13 +66,22 @@ impl Reindeer {
 
     // |...fly...|... rest ...|...fly...|...rest...| -> cycle times
     //                              ^ delta_sec (example) < fly_time
-    pub fn distance_after(&self, secs: i32) -> i32 {
+    pub fn distance_after(&self, secs: i32) -> Result<i32> {
         let cycle = self.fly_time + self.rest_time;
         let iterations = secs / cycle;
         let delta_sec = secs % cycle;
-        let cycles_dist = iterations * self.speed * self.fly_time;
-        let delta_dist = min(delta_sec, self.fly_time) * self.speed;
-        cycles_dist + delta_dist
+        iterations
+            .checked_mul(self.speed)
+            .and_then(|dist| dist.checked_mul(self.fly_time))
+            .and_then(|dist| {
+                dist.checked_add(min(delta_sec, self.fly_time).checked_mul(self.speed)?)
+            })
+            .with_context(|| {
+                format!(
+                    "Distance of {} after {secs} seconds out of range",
+                    self.name
+                )
+            })
     }
 }
 
@@ -53,34 +91,27 @@ pub struct ReindeerList {
 
 impl ReindeerList {
     pub fn from_str(input: &str) -> Result<Self> {
-        let mut v = Vec::<Reindeer>::new();
-        for line in input.lines() {
-            if line.trim().is_empty() {
-                continue;
-            }
-            let rd = Reindeer::from_str(line)?;
-            v.push(rd);
-        }
+        let v = parse_lines(input, Reindeer::from_str)?;
         Ok(Self { list: v })
     }
 
-    pub fn winning_distance_after(self, secs: i32) -> i32 {
+    pub fn winning_distance_after(self, secs: i32) -> Result<i32> {
         let mut winning_distance = 0;
         for rd in &self.list {
-            let d = rd.distance_after(secs);
+            let d = rd.distance_after(secs)?;
             if d > winning_distance {
                 winning_distance = d;
             }
         }
-        winning_distance
+        Ok(winning_distance)
     }
 
-    pub fn max_winning_points_after(&mut self, secs: i32) -> i32 {
+    pub fn max_winning_points_after(&mut self, secs: i32) -> Result<i32> {
         for s in 1..=secs {
             // Advance reindeers by 1 second and get max dist
             let mut max_dist = 0;
             for deer in self.list.iter_mut() {
-                let dist = deer.distance_after(s);
+                let dist = deer.distance_after(s)?;
                 deer.dist = dist;
                 max_dist = max(max_dist, deer.dist);
             }
@@ -92,13 +123,14 @@ impl ReindeerList {
             }
         }
         // Return the max points among all deers
-        self.list.iter().map(|deer| deer.points).max().unwrap_or(0)
+        Ok(self.list.iter().map(|deer| deer.points).max().unwrap_or(0))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
     use test_case::test_case;
 
     const INPUT: &str = r#"
@@ -110,14 +142,56 @@ mod tests {
     #[test_case(1000, 1056, "Dancer can fly 16 km/s for 11 seconds, but then must rest for 162 seconds."; "tc2")]
     fn test_distance_after(secs: i32, expected: i32, input: &str) {
         let rdv = ReindeerList::from_str(input).unwrap();
-        let d1 = rdv.list[0].distance_after(secs);
+        let d1 = rdv.list[0].distance_after(secs).unwrap();
         assert_eq!(d1, expected);
     }
 
     #[test]
     fn winning_distance() {
         let rdv = ReindeerList::from_str(INPUT).unwrap();
-        let w = rdv.winning_distance_after(1000);
+        let w = rdv.winning_distance_after(1000).unwrap();
         assert_eq!(w, 1120);
     }
+
+    #[test]
+    fn bad_reindeer() {
+        let err = Reindeer::from_str(
+            "Comet can fly fast km/s for 10 seconds, but then must rest for 127 seconds.",
+        )
+        .unwrap_err();
+        assert_eq!(err.span, 14..18);
+        let err = Reindeer::from_str(
+            "Comet can fly 14 km/s for 0 seconds, but then must rest for 0 seconds.",
+        )
+        .unwrap_err();
+        assert_eq!(err.span.len(), 70);
+        let err = Reindeer::from_str(
+            "Comet can fly 2147483648 km/s for 10 seconds, but then must rest for 127 seconds.",
+        )
+        .unwrap_err();
+        assert!(err.message.contains("too large"));
+    }
+
+    #[test]
+    fn distance_overflow() {
+        let rd = Reindeer::from_str(
+            "Comet can fly 2147483647 km/s for 10 seconds, but then must rest for 127 seconds.",
+        )
+        .unwrap();
+        assert_eq!(rd.distance_after(1).unwrap(), 2147483647);
+        assert!(rd.distance_after(2).is_err());
+        assert!(rd.distance_after(1000).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn from_str_never_panics(
+            s in "Comet can fly [0-9-]{1,11} km/s for [0-9-]{1,11} seconds, but then must rest for [0-9-]{1,11} seconds.|\\PC*",
+            secs in 0..10_000_i32
+        ) {
+            if let Ok(rd) = Reindeer::from_str(&s) {
+                let _ = rd.distance_after(secs);
+            }
+        }
+    }
 }
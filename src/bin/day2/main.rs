@@ -1,10 +1,108 @@
+mod packing;
 mod part1;
 mod part2;
+mod policy;
 mod prism;
+mod report;
 
 use anyhow::Result;
-fn main() -> Result<()> {
-    part1::run()?;
-    part2::run()?;
+use clap::{Subcommand, ValueEnum};
+use common::parse_error::parse_lines;
+use common::registry::{self, Registry};
+use common::{Answer, InputSource, Solution};
+use prism::Prism;
+use report::Report;
+use std::path::PathBuf;
+
+// Packages ranked as the most expensive in a report, unless `--top` says otherwise
+const TOP_PACKAGES: usize = 10;
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    fn parses(&self) -> bool {
+        true
+    }
+
+    fn parse(&self, input: &str) -> Result<()> {
+        parse_lines(input, str::parse::<Prism>)?;
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        part1::solve(input)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        part2::solve(input)
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(2, Day2);
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the breakdown of every package
+    Report {
+        #[arg(value_enum)]
+        format: ReportFormat,
+        /// Number of the most expensive packages to rank
+        #[arg(long, default_value_t = TOP_PACKAGES)]
+        top: usize,
+        /// Input file to use instead of the day's input.txt, "-" reads stdin
+        input: Option<PathBuf>,
+    },
+    /// Pack every package into containers of the given LxWxH size
+    Pack {
+        container: Prism,
+        /// Input file to use instead of the day's input.txt, "-" reads stdin
+        input: Option<PathBuf>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ReportFormat {
+    Json,
+    Csv,
+}
+
+fn run(command: Command) -> Result<()> {
+    match command {
+        Command::Report { format, top, input } => report(format, top, input),
+        Command::Pack { container, input } => pack(&container, input),
+    }
+}
+
+fn report(format: ReportFormat, top: usize, input: Option<PathBuf>) -> Result<()> {
+    let report = Report::from_input(&InputSource::new(input).read(2)?, top)?;
+    match format {
+        ReportFormat::Json => println!("{}", report.to_json()?),
+        ReportFormat::Csv => print!("{}", report.to_csv()),
+    }
     Ok(())
 }
+
+fn pack(container: &Prism, input: Option<PathBuf>) -> Result<()> {
+    let packages = parse_lines(&InputSource::new(input).read(2)?, str::parse::<Prism>)?;
+    let packing = packing::pack(&packages, container)?;
+    for (i, c) in packing.containers.iter().enumerate() {
+        println!(
+            "Container {}: {} packages, {:.1}% full",
+            i + 1,
+            c.placements.len(),
+            100.0 * packing.fill_ratio(i)
+        );
+    }
+    println!(
+        "{} containers of {container} needed, {:.1}% full overall",
+        packing.container_count(),
+        100.0 * packing.overall_fill_ratio()
+    );
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    registry::main_with(register, run)
+}
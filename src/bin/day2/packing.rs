@@ -0,0 +1,258 @@
+use super::prism::Prism;
+use anyhow::{Result, bail};
+use itertools::Itertools;
+
+// An empty cuboid left in a container, `position` is its corner nearest to the origin
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Space {
+    position: [u32; 3],
+    size: [u32; 3],
+}
+
+impl Space {
+    fn fits(&self, size: [u32; 3]) -> bool {
+        self.size
+            .iter()
+            .zip(size)
+            .all(|(&free, needed)| needed <= free)
+    }
+
+    // Guillotine cut around a box of `size` placed at `position`: what is left to its right,
+    // in front of it and on top of it, three spaces that never overlap
+    fn split(&self, size: [u32; 3]) -> impl Iterator<Item = Space> {
+        let [x, y, z] = self.position;
+        let [sx, sy, sz] = self.size;
+        let [bx, by, bz] = size;
+        [
+            Space {
+                position: [x + bx, y, z],
+                size: [sx - bx, sy, sz],
+            },
+            Space {
+                position: [x, y + by, z],
+                size: [bx, sy - by, sz],
+            },
+            Space {
+                position: [x, y, z + bz],
+                size: [bx, by, sz - bz],
+            },
+        ]
+        .into_iter()
+        .filter(|space| space.size.iter().all(|&side| side > 0))
+    }
+}
+
+// Where one package went, `size` is its oriented length, width and height
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Placement {
+    pub package: usize,
+    pub position: [u32; 3],
+    pub size: [u32; 3],
+}
+
+#[derive(Clone, Debug)]
+pub struct Container {
+    pub placements: Vec<Placement>,
+    // Bottom first, then back to front and left to right
+    free: Vec<Space>,
+}
+
+impl Container {
+    fn new(size: [u32; 3]) -> Self {
+        Self {
+            placements: Vec::new(),
+            free: vec![Space {
+                position: [0; 3],
+                size,
+            }],
+        }
+    }
+
+    // Puts the package in the first free space one of its orientations fits in
+    fn try_place(&mut self, package: usize, orientations: &[[u32; 3]]) -> bool {
+        let found = self.free.iter().enumerate().find_map(|(i, space)| {
+            let size = orientations.iter().find(|&&size| space.fits(size))?;
+            Some((i, *size))
+        });
+        let Some((i, size)) = found else {
+            return false;
+        };
+        let space = self.free.remove(i);
+        self.placements.push(Placement {
+            package,
+            position: space.position,
+            size,
+        });
+        self.free.extend(space.split(size));
+        self.free
+            .sort_by_key(|space| (space.position[2], space.position[1], space.position[0]));
+        true
+    }
+
+    pub fn used_volume(&self) -> u128 {
+        self.placements.iter().map(|p| volume(p.size)).sum()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Packing {
+    pub container_size: [u32; 3],
+    pub containers: Vec<Container>,
+}
+
+impl Packing {
+    pub fn container_count(&self) -> usize {
+        self.containers.len()
+    }
+
+    // Share of the container's volume taken by its packages, between 0 and 1
+    pub fn fill_ratio(&self, container: usize) -> f64 {
+        self.containers[container].used_volume() as f64 / volume(self.container_size) as f64
+    }
+
+    // Same over every container used
+    pub fn overall_fill_ratio(&self) -> f64 {
+        if self.containers.is_empty() {
+            return 0.0;
+        }
+        let used: u128 = self.containers.iter().map(Container::used_volume).sum();
+        used as f64 / (volume(self.container_size) * self.containers.len() as u128) as f64
+    }
+}
+
+// Volumes of u32 sides always fit in a u128
+fn volume(size: [u32; 3]) -> u128 {
+    size.iter().map(|&side| side as u128).product()
+}
+
+// Every distinct way to lay a package down, the flattest first
+fn orientations(prism: &Prism) -> Vec<[u32; 3]> {
+    let mut orientations: Vec<[u32; 3]> = prism
+        .dimensions()
+        .into_iter()
+        .permutations(3)
+        .map(|sides| [sides[0], sides[1], sides[2]])
+        .unique()
+        .collect();
+    orientations.sort_by_key(|&[l, w, h]| (h, std::cmp::Reverse((l, w))));
+    orientations
+}
+
+// First fit decreasing: the largest packages are placed first, each in the first container
+// with room for it in any orientation, a new container is opened when none has room.
+// `package` in the placements is the index of the package in `packages`.
+pub fn pack(packages: &[Prism], container: &Prism) -> Result<Packing> {
+    let container_size = container.dimensions();
+    let empty = Space {
+        position: [0; 3],
+        size: container_size,
+    };
+    let mut order: Vec<usize> = (0..packages.len()).collect();
+    // Stable, equal volumes keep the order of the list
+    order.sort_by_key(|&i| std::cmp::Reverse(volume(packages[i].dimensions())));
+
+    let mut containers: Vec<Container> = Vec::new();
+    for i in order {
+        let orientations = orientations(&packages[i]);
+        if !orientations.iter().any(|&size| empty.fits(size)) {
+            bail!(
+                "Package {} ({}) does not fit in a {container} container",
+                i + 1,
+                packages[i]
+            );
+        }
+        if !containers.iter_mut().any(|c| c.try_place(i, &orientations)) {
+            let mut new = Container::new(container_size);
+            new.try_place(i, &orientations);
+            containers.push(new);
+        }
+    }
+    Ok(Packing {
+        container_size,
+        containers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use test_case::test_case;
+
+    fn prisms(list: &[&str]) -> Vec<Prism> {
+        list.iter().map(|s| s.parse().unwrap()).collect()
+    }
+
+    #[test_case(&["1x1x1"; 8], "2x2x2", 1; "exactly full")]
+    #[test_case(&["1x1x1"; 9], "2x2x2", 2; "one too many")]
+    #[test_case(&["3x1x1", "1x3x1", "1x1x3"], "3x3x1", 1; "rotated flat")]
+    #[test_case(&["2x2x2", "1x1x1", "1x1x1"], "3x2x2", 1; "small ones beside the large one")]
+    #[test_case(&[], "1x1x1", 0; "nothing to pack")]
+    fn container_count(packages: &[&str], container: &str, expected: usize) {
+        let container: Prism = container.parse().unwrap();
+        let packing = pack(&prisms(packages), &container).unwrap();
+        assert_eq!(packing.container_count(), expected);
+    }
+
+    #[test]
+    fn fill_ratio() {
+        let container: Prism = "2x2x2".parse().unwrap();
+        let packing = pack(&prisms(&["1x1x1"; 10]), &container).unwrap();
+        assert_eq!(packing.fill_ratio(0), 1.0);
+        assert_eq!(packing.fill_ratio(1), 0.25);
+        assert_eq!(packing.overall_fill_ratio(), 10.0 / 16.0);
+    }
+
+    #[test]
+    fn package_too_large() {
+        let container: Prism = "2x2x2".parse().unwrap();
+        let err = pack(&prisms(&["1x1x1", "1x1x3"]), &container).unwrap_err();
+        assert!(err.to_string().contains("Package 2 (1x1x3)"));
+    }
+
+    fn overlap(a: &Placement, b: &Placement) -> bool {
+        (0..3).all(|axis| {
+            a.position[axis] < b.position[axis] + b.size[axis]
+                && b.position[axis] < a.position[axis] + a.size[axis]
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn every_package_placed_once_without_overlap(
+            sides in prop::collection::vec((1..5_u32, 1..5_u32, 1..5_u32), 0..40)
+        ) {
+            let packages: Vec<Prism> = sides
+                .iter()
+                .map(|(l, w, h)| format!("{l}x{w}x{h}").parse().unwrap())
+                .collect();
+            let container: Prism = "6x5x4".parse().unwrap();
+            let packing = pack(&packages, &container).unwrap();
+
+            let mut placed: Vec<usize> = packing
+                .containers
+                .iter()
+                .flat_map(|c| c.placements.iter().map(|p| p.package))
+                .collect();
+            placed.sort();
+            prop_assert_eq!(placed, (0..packages.len()).collect::<Vec<_>>());
+
+            for c in &packing.containers {
+                prop_assert!(!c.placements.is_empty());
+                for (i, a) in c.placements.iter().enumerate() {
+                    let mut sorted = a.size;
+                    sorted.sort();
+                    let mut expected = packages[a.package].dimensions();
+                    expected.sort();
+                    prop_assert_eq!(sorted, expected);
+                    for axis in 0..3 {
+                        prop_assert!(a.position[axis] + a.size[axis] <= packing.container_size[axis]);
+                    }
+                    for b in &c.placements[i + 1..] {
+                        prop_assert!(!overlap(a, b));
+                    }
+                }
+            }
+        }
+    }
+}
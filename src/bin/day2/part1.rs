@@ -1,18 +1,18 @@
-use crate::prism::Prism;
+use super::prism::{Prism, checked_total, too_large};
 use anyhow::Result;
+use common::parse_error::parse_lines;
+use common::{Answer, ParseError};
 
-fn amount_of_wrapping_paper(single_line: &str) -> Result<u32> {
-    let prism = Prism::from_str(single_line)?;
-    Ok(prism.total_wrapping_paper())
+fn amount_of_wrapping_paper(single_line: &str) -> Result<u64, ParseError> {
+    let prism: Prism = single_line.parse()?;
+    prism
+        .total_wrapping_paper()
+        .ok_or_else(|| too_large(single_line, "wrapping paper"))
 }
 
-pub fn run() -> Result<()> {
-    let input = common::read_input(2)?;
-    let total_sum: u32 = input.lines().try_fold(0_u32, |acc, line| {
-        amount_of_wrapping_paper(line).map(|v| acc + v)
-    })?;
-    println!("Total wrapping paper = {}", total_sum);
-    Ok(())
+pub fn solve(input: &str) -> Result<Answer> {
+    let amounts = parse_lines(input, amount_of_wrapping_paper)?;
+    Ok(checked_total(&amounts)?.into())
 }
 
 #[cfg(test)]
@@ -21,8 +21,16 @@ mod tests {
 
     #[test_case("2x3x4", 58; "measure 2x3x4")]
     #[test_case("1x1x10", 43; "measure 1x1x10")]
-    fn amount_of_wrapping_paper(measures: &str, expected: u32) {
+    fn amount_of_wrapping_paper(measures: &str, expected: u64) {
         let result = super::amount_of_wrapping_paper(measures).unwrap();
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn package_too_large() {
+        let err = super::solve("2x3x4\n4294967295x4294967295x4294967295\n").unwrap_err();
+        let err = err.downcast_ref::<common::ParseError>().unwrap();
+        assert_eq!(err.line, Some(2));
+        assert!(err.message.contains("wrapping paper"));
+    }
 }
@@ -0,0 +1,206 @@
+use super::prism::Prism;
+
+// How much paper and ribbon a package needs beyond its bare geometry. Amounts are `None`
+// when they do not fit in a u64, like the `Prism` measures they are built from.
+pub trait WrappingPolicy {
+    // Extra paper on top of the surface area
+    fn slack(&self, prism: &Prism) -> Option<u64>;
+
+    // Ribbon tied into the bow, on top of the smallest perimeter
+    fn bow(&self, prism: &Prism) -> Option<u64>;
+
+    fn wrapping_paper(&self, prism: &Prism) -> Option<u64> {
+        prism.surface_area()?.checked_add(self.slack(prism)?)
+    }
+
+    fn ribbon(&self, prism: &Prism) -> Option<u64> {
+        self.bow(prism)?.checked_add(prism.smallest_perimeter())
+    }
+}
+
+// The rules from the puzzle: the smallest side as slack, the volume as bow
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ElvesPolicy;
+
+impl WrappingPolicy for ElvesPolicy {
+    fn slack(&self, prism: &Prism) -> Option<u64> {
+        Some(prism.smallest_side_area())
+    }
+
+    fn bow(&self, prism: &Prism) -> Option<u64> {
+        prism.volume()
+    }
+}
+
+// Slack as a percentage of the surface area, rounded up. The bow comes from `base`. Not
+// needed by the two parts, which keep to the puzzle's rules.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug)]
+pub struct SlackPercent<P = ElvesPolicy> {
+    pub base: P,
+    pub percent: u64,
+}
+
+#[allow(dead_code)]
+impl SlackPercent {
+    pub fn new(percent: u64) -> Self {
+        Self {
+            base: ElvesPolicy,
+            percent,
+        }
+    }
+}
+
+impl<P: WrappingPolicy> WrappingPolicy for SlackPercent<P> {
+    fn slack(&self, prism: &Prism) -> Option<u64> {
+        Some(
+            prism
+                .surface_area()?
+                .checked_mul(self.percent)?
+                .div_ceil(100),
+        )
+    }
+
+    fn bow(&self, prism: &Prism) -> Option<u64> {
+        self.base.bow(prism)
+    }
+}
+
+// No bow longer than `max`, whatever `base` asks for. A bow too long to count is capped too.
+// Not needed by the two parts either.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug)]
+pub struct CappedBow<P = ElvesPolicy> {
+    pub base: P,
+    pub max: u64,
+}
+
+#[allow(dead_code)]
+impl CappedBow {
+    pub fn new(max: u64) -> Self {
+        Self {
+            base: ElvesPolicy,
+            max,
+        }
+    }
+}
+
+impl<P: WrappingPolicy> WrappingPolicy for CappedBow<P> {
+    fn slack(&self, prism: &Prism) -> Option<u64> {
+        self.base.slack(prism)
+    }
+
+    fn bow(&self, prism: &Prism) -> Option<u64> {
+        Some(
+            self.base
+                .bow(prism)
+                .map_or(self.max, |bow| bow.min(self.max)),
+        )
+    }
+}
+
+// Prices paper per square foot and ribbon per foot, in the smallest unit of some currency.
+// It is applied to the amounts a policy counts rather than being a policy itself, so that
+// every amount of a report stays in feet or square feet and its columns add up. Only the
+// tests price a report so far.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug)]
+pub struct Pricing {
+    pub paper_price: u64,
+    pub ribbon_price: u64,
+}
+
+#[allow(dead_code)]
+impl Pricing {
+    pub fn new(paper_price: u64, ribbon_price: u64) -> Self {
+        Self {
+            paper_price,
+            ribbon_price,
+        }
+    }
+
+    pub fn paper(&self, square_feet: u64) -> Option<u64> {
+        square_feet.checked_mul(self.paper_price)
+    }
+
+    pub fn ribbon(&self, feet: u64) -> Option<u64> {
+        feet.checked_mul(self.ribbon_price)
+    }
+
+    // Price of a package, or of many, from their wrapping paper and ribbon
+    pub fn bill(&self, wrapping_paper: u64, ribbon: u64) -> Option<u64> {
+        self.paper(wrapping_paper)?
+            .checked_add(self.ribbon(ribbon)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn prism(s: &str) -> Prism {
+        s.parse().unwrap()
+    }
+
+    #[test_case("2x3x4", 58, 34; "measure 2x3x4")]
+    #[test_case("1x1x10", 43, 14; "measure 1x1x10")]
+    fn elves_policy(measures: &str, paper: u64, ribbon: u64) {
+        let p = prism(measures);
+        assert_eq!(ElvesPolicy.wrapping_paper(&p), Some(paper));
+        assert_eq!(ElvesPolicy.ribbon(&p), Some(ribbon));
+    }
+
+    #[test_case(0, 52; "no slack")]
+    #[test_case(10, 52 + 6; "rounded up")]
+    #[test_case(50, 52 + 26; "half")]
+    fn slack_percent(percent: u64, paper: u64) {
+        let policy = SlackPercent::new(percent);
+        assert_eq!(policy.wrapping_paper(&prism("2x3x4")), Some(paper));
+        assert_eq!(policy.ribbon(&prism("2x3x4")), Some(34));
+    }
+
+    #[test_case(100, 34; "under the cap")]
+    #[test_case(5, 15; "capped")]
+    fn capped_bow(max: u64, ribbon: u64) {
+        let policy = CappedBow::new(max);
+        assert_eq!(policy.ribbon(&prism("2x3x4")), Some(ribbon));
+        assert_eq!(policy.wrapping_paper(&prism("2x3x4")), Some(58));
+    }
+
+    #[test]
+    fn capped_bow_of_a_huge_package() {
+        let p = prism("4294967295x4294967295x4294967295");
+        assert_eq!(ElvesPolicy.bow(&p), None);
+        assert_eq!(CappedBow::new(1000).bow(&p), Some(1000));
+    }
+
+    #[test]
+    fn pricing() {
+        let pricing = Pricing::new(3, 5);
+        assert_eq!(pricing.paper(58), Some(58 * 3));
+        assert_eq!(pricing.ribbon(34), Some(34 * 5));
+        assert_eq!(pricing.bill(58, 34), Some(58 * 3 + 34 * 5));
+        assert_eq!(Pricing::new(u64::MAX, 1).paper(58), None);
+        assert_eq!(Pricing::new(u64::MAX, 1).bill(1, 1), None);
+    }
+
+    #[test]
+    fn policies_compose() {
+        let policy = CappedBow {
+            base: SlackPercent::new(50),
+            max: 5,
+        };
+        let p = prism("2x3x4");
+        assert_eq!(policy.wrapping_paper(&p), Some(52 + 26));
+        assert_eq!(policy.ribbon(&p), Some(10 + 5));
+        let pricing = Pricing::new(2, 10);
+        assert_eq!(
+            pricing.bill(
+                policy.wrapping_paper(&p).unwrap(),
+                policy.ribbon(&p).unwrap()
+            ),
+            Some((52 + 26) * 2 + (10 + 5) * 10)
+        );
+    }
+}
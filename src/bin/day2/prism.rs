@@ -1,57 +1,150 @@
-use anyhow::Result;
+use super::policy::{ElvesPolicy, WrappingPolicy};
+use anyhow::{Context, Result};
+use common::ParseError;
+use std::fmt::Display;
+use std::num::IntErrorKind;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Prism {
     l: u32,
     w: u32,
     h: u32,
 }
 
-impl Prism {
-    pub fn from_str(s: &str) -> Result<Self> {
-        let dims: Vec<u32> = s.split('x').map(|x| x.parse::<u32>().unwrap()).collect();
+impl FromStr for Prism {
+    type Err = ParseError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let s = line.trim();
+        let tokens: Vec<&str> = s.split('x').collect();
+        let [l, w, h] = tokens[..] else {
+            return Err(ParseError::at(
+                line,
+                s,
+                format!("Expected LxWxH, found {} dimension(s)", tokens.len()),
+            ));
+        };
         Ok(Prism {
-            l: dims[0],
-            w: dims[1],
-            h: dims[2],
+            l: parse_dimension(line, l)?,
+            w: parse_dimension(line, w)?,
+            h: parse_dimension(line, h)?,
         })
     }
+}
+
+// `token` is a slice of `line`, so that errors point at the bad dimension
+fn parse_dimension(line: &str, token: &str) -> Result<u32, ParseError> {
+    if token.is_empty() {
+        return Err(ParseError::at(line, token, "Missing dimension"));
+    }
+    // Only plain digits, u32 parsing alone would also accept a leading '+'
+    if !token.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(ParseError::at(
+            line,
+            token,
+            format!("Bad dimension '{token}'"),
+        ));
+    }
+    match token.parse::<u32>() {
+        Ok(0) => Err(ParseError::at(line, token, "Dimensions cannot be zero")),
+        Ok(dim) => Ok(dim),
+        Err(err) if *err.kind() == IntErrorKind::PosOverflow => Err(ParseError::at(
+            line,
+            token,
+            format!("Dimension '{token}' is too large, at most {}", u32::MAX),
+        )),
+        Err(_) => Err(ParseError::at(
+            line,
+            token,
+            format!("Bad dimension '{token}'"),
+        )),
+    }
+}
+
+impl Display for Prism {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}x{}x{}", self.l, self.w, self.h)
+    }
+}
+
+// Computed in u64, where the products of two sides always fit. Sums of them and the
+// volume may not, those are `None` when they overflow.
+impl Prism {
+    // Length, width and height, as read
+    pub fn dimensions(&self) -> [u32; 3] {
+        [self.l, self.w, self.h]
+    }
+
+    fn sides(&self) -> [u64; 3] {
+        [self.l as u64, self.w as u64, self.h as u64]
+    }
+
+    fn side_areas(&self) -> [u64; 3] {
+        let [l, w, h] = self.sides();
+        [l * w, w * h, h * l]
+    }
 
-    pub fn surface_area(&self) -> u32 {
-        2 * (self.l * self.w + self.w * self.h + self.h * self.l)
+    pub fn surface_area(&self) -> Option<u64> {
+        let [a, b, c] = self.side_areas();
+        a.checked_add(b)?.checked_add(c)?.checked_mul(2)
     }
 
-    fn smallest_side_area(&self) -> u32 {
-        let side1 = self.l * self.w;
-        let side2 = self.w * self.h;
-        let side3 = self.h * self.l;
-        *[side1, side2, side3].iter().min().unwrap()
+    pub fn smallest_side_area(&self) -> u64 {
+        self.side_areas().into_iter().min().unwrap()
     }
 
-    pub fn total_wrapping_paper(&self) -> u32 {
-        self.surface_area() + self.smallest_side_area()
+    // Under the elves' rules, see `WrappingPolicy` for the others
+    pub fn total_wrapping_paper(&self) -> Option<u64> {
+        ElvesPolicy.wrapping_paper(self)
     }
 
-    pub fn smallest_perimeter(&self) -> u32 {
-        let mut sides = vec![self.l, self.w, self.h];
+    pub fn smallest_perimeter(&self) -> u64 {
+        let mut sides = self.sides();
         sides.sort();
         2 * (sides[0] + sides[1])
     }
 
-    pub fn volume(&self) -> u32 {
-        self.l * self.w * self.h
+    pub fn volume(&self) -> Option<u64> {
+        let [l, w, h] = self.sides();
+        (l * w).checked_mul(h)
     }
 
-    pub fn total_ribbon(&self) -> u32 {
-        self.smallest_perimeter() + self.volume()
+    pub fn total_ribbon(&self) -> Option<u64> {
+        ElvesPolicy.ribbon(self)
     }
 }
 
+// Error for the package on `line` when `what` it needs cannot be counted
+pub fn too_large(line: &str, what: &str) -> ParseError {
+    ParseError::at(
+        line,
+        line.trim(),
+        format!("Package is too large, its {what} does not fit in 64 bits"),
+    )
+}
+
+// Sum of the amounts needed by every package, an error rather than a wrong answer when
+// it does not fit in one
+pub fn checked_total(amounts: &[u64]) -> Result<i64> {
+    amounts
+        .iter()
+        .try_fold(0_i64, |total, &amount| {
+            total.checked_add(i64::try_from(amount).ok()?)
+        })
+        .context("The total is too large to be an answer")
+}
+
 #[cfg(test)]
 mod tests {
     use super::Prism;
+    use proptest::prelude::*;
+    use std::ops::Range;
+    use test_case::test_case;
 
     #[test]
     fn test_prism_from_str() {
-        let p = Prism::from_str("2x3x4").unwrap();
+        let p = "2x3x4".parse::<Prism>().unwrap();
         assert_eq!(p.l, 2);
         assert_eq!(p.w, 3);
         assert_eq!(p.h, 4);
@@ -59,19 +152,85 @@ mod tests {
 
     #[test]
     fn test_surface_area() {
-        let p = Prism::from_str("2x3x4").unwrap();
-        assert_eq!(p.surface_area(), 52);
+        let p = "2x3x4".parse::<Prism>().unwrap();
+        assert_eq!(p.surface_area(), Some(52));
     }
 
     #[test]
     fn test_smallest_side_area() {
-        let p = Prism::from_str("2x3x4").unwrap();
+        let p = "2x3x4".parse::<Prism>().unwrap();
         assert_eq!(p.smallest_side_area(), 6);
     }
 
     #[test]
     fn test_smallest_perimeter() {
-        let p = Prism::from_str("2x3x4").unwrap();
+        let p = "2x3x4".parse::<Prism>().unwrap();
         assert_eq!(p.smallest_perimeter(), 10);
     }
+
+    #[test]
+    fn test_largest_prism() {
+        let max = u32::MAX as u64;
+        let p = Prism {
+            l: u32::MAX,
+            w: u32::MAX,
+            h: 1,
+        };
+        assert_eq!(p.smallest_side_area(), max);
+        assert_eq!(p.smallest_perimeter(), 2 * (max + 1));
+        assert_eq!(p.volume(), Some(max * max));
+        assert_eq!(p.surface_area(), None);
+        assert_eq!(p.total_wrapping_paper(), None);
+        // The volume only just fits, not once the bow is added
+        assert_eq!(p.total_ribbon(), None);
+        let p = Prism { h: u32::MAX, ..p };
+        assert_eq!(p.volume(), None);
+    }
+
+    #[test_case(&[], Some(0); "empty")]
+    #[test_case(&[1, 2, 3], Some(6); "small")]
+    #[test_case(&[i64::MAX as u64], Some(i64::MAX); "largest")]
+    #[test_case(&[i64::MAX as u64, 1], None; "sum overflows")]
+    #[test_case(&[u64::MAX], None; "amount overflows")]
+    fn test_checked_total(amounts: &[u64], expected: Option<i64>) {
+        assert_eq!(super::checked_total(amounts).ok(), expected);
+    }
+
+    #[test_case("2x?x4", 2..3; "non numeric")]
+    #[test_case("2x+3x4", 2..4; "sign")]
+    #[test_case("2x3", 0..3; "too few")]
+    #[test_case("2x3x4x5", 0..7; "too many")]
+    #[test_case("2xx4", 2..2; "missing")]
+    #[test_case("2x0x4", 2..3; "zero")]
+    #[test_case("2x3x4294967296", 4..14; "overflow")]
+    #[test_case(" 2x3 ", 1..4; "trimmed")]
+    fn test_prism_from_bad_str(s: &str, span: Range<usize>) {
+        let err = s.parse::<Prism>().unwrap_err();
+        assert_eq!(err.span, span);
+    }
+
+    #[test]
+    fn test_display() {
+        let p: Prism = " 2x3x4\r".parse().unwrap();
+        assert_eq!(p.to_string(), "2x3x4");
+    }
+
+    proptest! {
+        #[test]
+        fn from_str_never_panics(s in "[0-9x ]{0,15}|\\PC*") {
+            let _ = s.parse::<Prism>();
+        }
+
+        #[test]
+        fn display_round_trips(l in 1..=u32::MAX, w in 1..=u32::MAX, h in 1..=u32::MAX) {
+            let prism = Prism { l, w, h };
+            prop_assert_eq!(prism.to_string().parse::<Prism>().unwrap(), prism);
+        }
+
+        #[test]
+        fn from_str_round_trips(l in 1..=u32::MAX, w in 1..=u32::MAX, h in 1..=u32::MAX) {
+            let s = format!("{l}x{w}x{h}");
+            prop_assert_eq!(s.parse::<Prism>().unwrap().to_string(), s);
+        }
+    }
 }
@@ -0,0 +1,244 @@
+use super::policy::{ElvesPolicy, WrappingPolicy};
+use super::prism::{Prism, too_large};
+use anyhow::{Context, Result};
+use common::ParseError;
+use common::parse_error::parse_lines;
+use serde::Serialize;
+
+const CSV_HEADER: &str = "package,length,width,height,surface_area,slack,wrapping_paper,smallest_perimeter,bow,ribbon,rank\n";
+
+// Everything the elves need for one package, `package` is its 1-based position in the list
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PackageReport {
+    pub package: usize,
+    pub length: u32,
+    pub width: u32,
+    pub height: u32,
+    pub surface_area: u64,
+    pub slack: u64,
+    pub wrapping_paper: u64,
+    pub smallest_perimeter: u64,
+    pub bow: u64,
+    pub ribbon: u64,
+}
+
+impl PackageReport {
+    fn new(package: usize, line: &str, policy: &dyn WrappingPolicy) -> Result<Self, ParseError> {
+        let prism: Prism = line.parse()?;
+        let [length, width, height] = prism.dimensions();
+        let needed = |amount: Option<u64>, what| amount.ok_or_else(|| too_large(line, what));
+        Ok(Self {
+            package,
+            length,
+            width,
+            height,
+            surface_area: needed(prism.surface_area(), "surface area")?,
+            slack: needed(policy.slack(&prism), "slack")?,
+            wrapping_paper: needed(policy.wrapping_paper(&prism), "wrapping paper")?,
+            smallest_perimeter: prism.smallest_perimeter(),
+            bow: needed(policy.bow(&prism), "bow")?,
+            ribbon: needed(policy.ribbon(&prism), "ribbon")?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Totals {
+    pub packages: usize,
+    pub surface_area: u64,
+    pub slack: u64,
+    pub wrapping_paper: u64,
+    pub smallest_perimeter: u64,
+    pub bow: u64,
+    pub ribbon: u64,
+}
+
+impl Totals {
+    fn add(&mut self, package: &PackageReport) -> Option<()> {
+        self.packages += 1;
+        self.surface_area = self.surface_area.checked_add(package.surface_area)?;
+        self.slack = self.slack.checked_add(package.slack)?;
+        self.wrapping_paper = self.wrapping_paper.checked_add(package.wrapping_paper)?;
+        self.smallest_perimeter = self
+            .smallest_perimeter
+            .checked_add(package.smallest_perimeter)?;
+        self.bow = self.bow.checked_add(package.bow)?;
+        self.ribbon = self.ribbon.checked_add(package.ribbon)?;
+        Some(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Report {
+    pub packages: Vec<PackageReport>,
+    pub totals: Totals,
+    // The most expensive packages: most wrapping paper first, then most ribbon
+    pub top: Vec<PackageReport>,
+}
+
+impl Report {
+    pub fn from_input(input: &str, top: usize) -> Result<Self> {
+        Self::from_input_with(input, top, &ElvesPolicy)
+    }
+
+    // Slack, bow, paper and ribbon as `policy` counts them
+    pub fn from_input_with(input: &str, top: usize, policy: &dyn WrappingPolicy) -> Result<Self> {
+        let mut package = 0;
+        let packages = parse_lines(input, |line| {
+            package += 1;
+            PackageReport::new(package, line, policy)
+        })?;
+
+        let mut totals = Totals::default();
+        for package in &packages {
+            totals
+                .add(package)
+                .with_context(|| format!("Totals overflow at package {}", package.package))?;
+        }
+
+        let mut by_cost: Vec<&PackageReport> = packages.iter().collect();
+        // Stable, so that equally expensive packages stay in list order
+        by_cost.sort_by_key(|p| std::cmp::Reverse((p.wrapping_paper, p.ribbon)));
+        let top = by_cost.into_iter().take(top).cloned().collect();
+
+        Ok(Self {
+            packages,
+            totals,
+            top,
+        })
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    // One row per package, followed by a "total" row. `rank` is the 1-based position of the
+    // package in `top`, empty for the packages that did not make it.
+    pub fn to_csv(&self) -> String {
+        let rank = |package| {
+            self.top
+                .iter()
+                .position(|p| p.package == package)
+                .map_or_else(String::new, |i| (i + 1).to_string())
+        };
+        let mut csv = String::from(CSV_HEADER);
+        for p in &self.packages {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{},{},{},{}\n",
+                p.package,
+                p.length,
+                p.width,
+                p.height,
+                p.surface_area,
+                p.slack,
+                p.wrapping_paper,
+                p.smallest_perimeter,
+                p.bow,
+                p.ribbon,
+                rank(p.package)
+            ));
+        }
+        let t = &self.totals;
+        csv.push_str(&format!(
+            "total,,,,{},{},{},{},{},{},\n",
+            t.surface_area, t.slack, t.wrapping_paper, t.smallest_perimeter, t.bow, t.ribbon
+        ));
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::policy::{CappedBow, Pricing};
+    use super::*;
+
+    #[test]
+    fn package_breakdown() {
+        let report = Report::from_input("2x3x4\n", 1).unwrap();
+        assert_eq!(
+            report.packages[0],
+            PackageReport {
+                package: 1,
+                length: 2,
+                width: 3,
+                height: 4,
+                surface_area: 52,
+                slack: 6,
+                wrapping_paper: 58,
+                smallest_perimeter: 10,
+                bow: 24,
+                ribbon: 34,
+            }
+        );
+    }
+
+    #[test]
+    fn totals_and_top() {
+        let report = Report::from_input("1x1x10\n\n2x3x4\n1x1x1\n", 2).unwrap();
+        assert_eq!(report.totals.packages, 3);
+        assert_eq!(report.totals.wrapping_paper, 43 + 58 + 7);
+        assert_eq!(report.totals.ribbon, 14 + 34 + 5);
+        let top: Vec<usize> = report.top.iter().map(|p| p.package).collect();
+        assert_eq!(top, vec![2, 1]);
+    }
+
+    #[test]
+    fn other_policy() {
+        let policy = CappedBow::new(5);
+        let report = Report::from_input_with("2x3x4\n1x1x10\n", 2, &policy).unwrap();
+        assert_eq!(report.packages[0].bow, 5);
+        assert_eq!(report.totals.ribbon, 15 + 9);
+    }
+
+    #[test]
+    fn priced_totals_add_up() {
+        let report = Report::from_input("2x3x4\n1x1x10\n", 2).unwrap();
+        let pricing = Pricing::new(3, 5);
+        let t = &report.totals;
+        assert_eq!(
+            pricing.bill(t.wrapping_paper, t.ribbon),
+            Some(101 * 3 + 48 * 5)
+        );
+        let rows: u64 = report
+            .packages
+            .iter()
+            .map(|p| pricing.bill(p.wrapping_paper, p.ribbon).unwrap())
+            .sum();
+        assert_eq!(pricing.bill(t.wrapping_paper, t.ribbon), Some(rows));
+    }
+
+    #[test]
+    fn too_large_package() {
+        let err = Report::from_input("1x1x1\n4294967295x4294967295x4294967295", 1).unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!(err.line, Some(2));
+    }
+
+    #[test]
+    fn machine_readable_output() {
+        let report = Report::from_input("2x3x4\n1x1x10\n", 1).unwrap();
+        assert_eq!(
+            report.to_csv(),
+            format!(
+                "{CSV_HEADER}1,2,3,4,52,6,58,10,24,34,1\n2,1,1,10,42,1,43,4,10,14,\ntotal,,,,94,7,101,14,34,48,\n"
+            )
+        );
+        let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(json["packages"][1]["height"], 10);
+        assert_eq!(json["totals"]["wrapping_paper"], 101);
+        assert_eq!(json["top"][0]["package"], 1);
+        assert_eq!(json["top"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn csv_ranks_the_top_packages() {
+        let report = Report::from_input("1x1x1\n2x3x4\n1x1x10\n", 2).unwrap();
+        let csv = report.to_csv();
+        let ranks: Vec<&str> = csv
+            .lines()
+            .skip(1)
+            .map(|row| row.rsplit(',').next().unwrap())
+            .collect();
+        assert_eq!(ranks, vec!["", "1", "2", ""]);
+    }
+}
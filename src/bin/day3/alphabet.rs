@@ -0,0 +1,49 @@
+use common::alphabet::Alphabet;
+
+// The puzzle's directions, as (dx, dy) with north going up
+pub const FOUR_WAY: [(char, (i32, i32)); 4] =
+    [('>', (1, 0)), ('<', (-1, 0)), ('^', (0, 1)), ('v', (0, -1))];
+
+// Diagonals on the square grid, for `MovementAlphabet::with_moves`
+#[allow(dead_code)]
+pub const DIAGONALS: [(char, (i32, i32)); 4] = [
+    ('↗', (1, 1)),
+    ('↖', (-1, 1)),
+    ('↘', (1, -1)),
+    ('↙', (-1, -1)),
+];
+
+// The six neighbours on a hex grid, in the axial (q, r) coordinates of `HexPos`
+#[allow(dead_code)]
+pub const HEX: [(char, (i32, i32)); 6] = [
+    ('>', (1, 0)),
+    ('<', (-1, 0)),
+    ('↗', (1, -1)),
+    ('↖', (0, -1)),
+    ('↘', (0, 1)),
+    ('↙', (-1, 1)),
+];
+
+// Delivers another present where the agent already is, e.g. `with_move('.', STAY)`
+#[allow(dead_code)]
+pub const STAY: (i32, i32) = (0, 0);
+
+// Which characters move an agent and by how much, as (dx, dy)
+pub type MovementAlphabet = Alphabet<(i32, i32)>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::alphabet::Symbol;
+    use test_case::test_case;
+
+    #[test_case(MovementAlphabet::new(FOUR_WAY), '^', Some(Symbol::Move((0, 1))); "north")]
+    #[test_case(MovementAlphabet::new(FOUR_WAY), '↗', None; "no diagonal")]
+    #[test_case(MovementAlphabet::new(FOUR_WAY).with_moves(DIAGONALS), '↙', Some(Symbol::Move((-1, -1))); "diagonal")]
+    #[test_case(MovementAlphabet::new(FOUR_WAY).with_move('.', STAY), '.', Some(Symbol::Move((0, 0))); "stay")]
+    #[test_case(MovementAlphabet::new(HEX), '^', None; "no north on hex")]
+    #[test_case(MovementAlphabet::new(HEX), '↖', Some(Symbol::Move((0, -1))); "hex")]
+    fn symbol(alphabet: MovementAlphabet, ch: char, expected: Option<Symbol<(i32, i32)>>) {
+        assert_eq!(alphabet.symbol(ch), expected);
+    }
+}
@@ -0,0 +1,71 @@
+use super::santa::Pos;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+// Where an agent can stand. The default position is the starting house and moves are the
+// (dx, dy) of a `MovementAlphabet`, None when they leave the range of the coordinates.
+pub trait Position: Copy + Default + Eq + Hash + Debug {
+    fn moved(self, dx: i32, dy: i32) -> Option<Self>;
+
+    // Fewest single moves from the starting house
+    fn distance_from_start(self) -> u64;
+}
+
+// The puzzle's square grid, distances being Manhattan ones
+impl Position for Pos {
+    fn moved(self, dx: i32, dy: i32) -> Option<Self> {
+        Some(Pos(self.0.checked_add(dx)?, self.1.checked_add(dy)?))
+    }
+
+    fn distance_from_start(self) -> u64 {
+        self.0.unsigned_abs() as u64 + self.1.unsigned_abs() as u64
+    }
+}
+
+// A house on a hex grid in axial coordinates, r growing towards the south. Only the
+// puzzle's square grid is walked by the binary.
+#[allow(dead_code)]
+#[derive(Hash, Eq, PartialEq, Ord, PartialOrd, Debug, Default, Clone, Copy)]
+pub struct HexPos {
+    pub q: i32,
+    pub r: i32,
+}
+
+impl Position for HexPos {
+    fn moved(self, dq: i32, dr: i32) -> Option<Self> {
+        Some(HexPos {
+            q: self.q.checked_add(dq)?,
+            r: self.r.checked_add(dr)?,
+        })
+    }
+
+    fn distance_from_start(self) -> u64 {
+        let (q, r) = (self.q as i64, self.r as i64);
+        (q.unsigned_abs() + r.unsigned_abs() + (q + r).unsigned_abs()) / 2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(Pos(3, -4), 7; "square")]
+    #[test_case(Pos(i32::MIN, i32::MIN), 1 << 32; "square far away")]
+    fn square_distance(pos: Pos, expected: u64) {
+        assert_eq!(pos.distance_from_start(), expected);
+    }
+
+    #[test_case(HexPos { q: 1, r: -1 }, 1; "neighbour")]
+    #[test_case(HexPos { q: 2, r: 1 }, 3; "same signs")]
+    #[test_case(HexPos { q: 3, r: -1 }, 3; "opposite signs")]
+    fn hex_distance(pos: HexPos, expected: u64) {
+        assert_eq!(pos.distance_from_start(), expected);
+    }
+
+    #[test]
+    fn out_of_range() {
+        assert_eq!(Pos(i32::MAX, 0).moved(1, 0), None);
+        assert_eq!(HexPos { q: 0, r: i32::MIN }.moved(0, -1), None);
+    }
+}
@@ -1,9 +1,115 @@
+mod alphabet;
+mod grid;
+mod map;
 mod part1;
 mod part2;
+mod paths;
 mod santa;
+
 use anyhow::Result;
-fn main() -> Result<()> {
-    part1::run()?;
-    part2::run()?;
+use clap::{Subcommand, ValueEnum};
+use common::registry::{self, Registry};
+use common::{Answer, InputSource, Solution};
+use map::DeliveryMap;
+use paths::PathAnalysis;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    fn part1(&self, input: &str) -> Result<Answer> {
+        part1::solve(input)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        part2::solve(input)
+    }
+
+    fn streams(&self) -> bool {
+        true
+    }
+
+    fn part1_from_reader(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        part1::solve_reader(reader)
+    }
+
+    fn part2_from_reader(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        part2::solve_reader(reader)
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(3, Day3);
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Draw the houses the agents delivered to
+    Render {
+        #[arg(value_enum)]
+        format: ImageFormat,
+        /// Number of agents taking turns at the directions
+        agents: usize,
+        /// Input file to use instead of the day's input.txt, "-" reads stdin
+        input: Option<PathBuf>,
+    },
+    /// Sum up the loops and meetings along the agents' paths
+    Analyse {
+        /// Number of agents taking turns at the directions
+        agents: usize,
+        /// Input file to use instead of the day's input.txt, "-" reads stdin
+        input: Option<PathBuf>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ImageFormat {
+    Ascii,
+    Ppm,
+}
+
+fn run(command: Command) -> Result<()> {
+    match command {
+        Command::Render {
+            format,
+            agents,
+            input,
+        } => render(format, agents, input),
+        Command::Analyse { agents, input } => analyse(agents, input),
+    }
+}
+
+fn render(format: ImageFormat, agents: usize, input: Option<PathBuf>) -> Result<()> {
+    let map = DeliveryMap::new(&InputSource::new(input).read(3)?, agents)?;
+    let image = match format {
+        ImageFormat::Ascii => map.to_ascii().into_bytes(),
+        ImageFormat::Ppm => map.to_ppm(),
+    };
+    std::io::stdout().write_all(&image)?;
+    Ok(())
+}
+
+fn analyse(agents: usize, input: Option<PathBuf>) -> Result<()> {
+    let analysis = PathAnalysis::new(&InputSource::new(input).read(3)?, agents)?;
+    let longest_loop = analysis.revisits.iter().map(|revisit| revisit.loop_length);
+    println!("Revisits: {}", analysis.revisits.len());
+    println!("Returns to start: {}", analysis.returns_to_start().count());
+    println!("Longest loop: {}", longest_loop.max().unwrap_or(0));
+    match analysis.first_meeting {
+        Some(meeting) => println!(
+            "First meeting: agents {} and {} at {:?} on step {}",
+            meeting.agents.0, meeting.agents.1, meeting.house, meeting.step
+        ),
+        None => println!("First meeting: never"),
+    }
+    for agent in 0..agents {
+        let farthest = analysis.distances_of(agent).max().unwrap_or(0);
+        println!("Agent {agent} farthest from start: {farthest}");
+    }
     Ok(())
 }
+
+fn main() -> Result<()> {
+    registry::main_with(register, run)
+}
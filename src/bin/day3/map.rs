@@ -0,0 +1,241 @@
+use super::santa::{self, Pos};
+use anyhow::Result;
+use std::collections::HashMap;
+use std::io::BufRead;
+
+// Colours of the agents in rendered images, cycled when there are more agents
+const PALETTE: [[u8; 3]; 6] = [
+    [255, 64, 64],
+    [64, 255, 64],
+    [64, 128, 255],
+    [255, 224, 64],
+    [255, 64, 255],
+    [64, 255, 255],
+];
+
+// Every house that got a present, with how many each agent delivered there
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeliveryMap {
+    agents: usize,
+    houses: HashMap<Pos, Vec<u64>>,
+}
+
+impl DeliveryMap {
+    // Agents take turns, as in `santa::deliver`
+    pub fn new(directions: &str, agents: usize) -> Result<Self> {
+        Self::from_reader(directions.as_bytes(), agents, santa::round_robin(agents))
+    }
+
+    pub fn from_reader(
+        reader: impl BufRead,
+        agents: usize,
+        assign: impl FnMut(usize) -> usize,
+    ) -> Result<Self> {
+        let mut houses: HashMap<Pos, Vec<u64>> = HashMap::new();
+        santa::walk(reader, agents, assign, |delivery| {
+            houses
+                .entry(delivery.house)
+                .or_insert_with(|| vec![0; agents])[delivery.agent] += 1;
+        })?;
+        Ok(Self { agents, houses })
+    }
+}
+
+// Queries for library use, the binary only renders the map
+#[allow(dead_code)]
+impl DeliveryMap {
+    pub fn agents(&self) -> usize {
+        self.agents
+    }
+
+    // Number of houses that got at least one present
+    pub fn len(&self) -> usize {
+        self.houses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.houses.is_empty()
+    }
+
+    pub fn presents_by(&self, house: Pos, agent: usize) -> u64 {
+        self.houses
+            .get(&house)
+            .and_then(|counts| counts.get(agent).copied())
+            .unwrap_or(0)
+    }
+
+    // Agents that delivered at least one present to `house`
+    pub fn agents_at(&self, house: Pos) -> Vec<usize> {
+        self.houses.get(&house).map_or_else(Vec::new, |counts| {
+            (0..counts.len())
+                .filter(|&agent| counts[agent] > 0)
+                .collect()
+        })
+    }
+
+    // Sorted, so that the result does not depend on the order of the hash map
+    pub fn houses_with_at_least(&self, presents: u64) -> Vec<Pos> {
+        let mut houses: Vec<Pos> = self
+            .houses
+            .keys()
+            .filter(|&&house| self.presents(house) >= presents)
+            .copied()
+            .collect();
+        houses.sort();
+        houses
+    }
+}
+
+impl DeliveryMap {
+    pub fn presents(&self, house: Pos) -> u64 {
+        self.houses
+            .get(&house)
+            .map_or(0, |counts| counts.iter().sum())
+    }
+
+    // The house with the most presents and how many it got, the smallest one on a tie
+    pub fn most_visited(&self) -> Option<(Pos, u64)> {
+        self.houses
+            .keys()
+            .map(|&house| (house, self.presents(house)))
+            .max_by_key(|&(house, presents)| (presents, std::cmp::Reverse(house)))
+    }
+
+    // Lowest and highest x and y of the houses, None when no present was delivered
+    pub fn bounding_box(&self) -> Option<(Pos, Pos)> {
+        let xs = self.houses.keys().map(|house| house.0);
+        let ys = self.houses.keys().map(|house| house.1);
+        Some((
+            Pos(xs.clone().min()?, ys.clone().min()?),
+            Pos(xs.max()?, ys.max()?),
+        ))
+    }
+
+    // Rows from north to south (y going down), columns from west to east
+    fn rows(&self) -> Vec<Vec<Pos>> {
+        let Some((min, max)) = self.bounding_box() else {
+            return Vec::new();
+        };
+        (min.1..=max.1)
+            .rev()
+            .map(|y| (min.0..=max.0).map(|x| Pos(x, y)).collect())
+            .collect()
+    }
+
+    // One character per house: '.' when it got nothing, the number of presents up to 9
+    // and '+' for more
+    pub fn to_ascii(&self) -> String {
+        let mut ascii = String::new();
+        for row in self.rows() {
+            for house in row {
+                ascii.push(match self.presents(house) {
+                    0 => '.',
+                    n @ 1..=9 => char::from_digit(n as u32, 10).unwrap(),
+                    _ => '+',
+                });
+            }
+            ascii.push('\n');
+        }
+        ascii
+    }
+
+    // Binary PPM (P6) with one pixel per house, in the colour of the agent that delivered
+    // the most there and brighter the more presents it got. Unvisited houses are black.
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let rows = self.rows();
+        let width = rows.first().map_or(0, Vec::len);
+        let most = self.most_visited().map_or(1, |(_, presents)| presents);
+        let mut ppm = format!("P6\n{width} {}\n255\n", rows.len()).into_bytes();
+        for house in rows.into_iter().flatten() {
+            let Some(counts) = self.houses.get(&house) else {
+                ppm.extend([0, 0, 0]);
+                continue;
+            };
+            let presents: u64 = counts.iter().sum();
+            let agent = (0..counts.len()).max_by_key(|&a| (counts[a], std::cmp::Reverse(a)));
+            let colour = PALETTE[agent.unwrap_or(0) % PALETTE.len()];
+            // From a quarter of the agent's colour for one present up to all of it
+            let level = 64 + 191 * (presents - 1) / (most - 1).max(1);
+            ppm.extend(colour.map(|c| (c as u64 * level / 255) as u8));
+        }
+        ppm
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test]
+    fn counts_per_agent() {
+        let map = DeliveryMap::new("^>v<", 2).unwrap();
+        // Santa goes north and back, the robot east and back, both start and end at home
+        assert_eq!(map.presents(Pos(0, 0)), 4);
+        assert_eq!(map.presents_by(Pos(0, 0), 1), 2);
+        assert_eq!(map.presents_by(Pos(0, 1), 0), 1);
+        assert_eq!(map.presents_by(Pos(0, 1), 1), 0);
+        assert_eq!(map.agents_at(Pos(1, 0)), vec![1]);
+        assert_eq!(map.agents_at(Pos(0, 0)), vec![0, 1]);
+        assert_eq!(map.agents_at(Pos(5, 5)), Vec::<usize>::new());
+        assert_eq!(map.len(), 3);
+    }
+
+    #[test_case("^v^v^v^v^v", 1, vec![Pos(0, 0), Pos(0, 1)]; "back and forth")]
+    #[test_case("^>v<", 1, vec![Pos(0, 0)]; "only home twice")]
+    #[test_case("", 1, vec![]; "nothing")]
+    #[test_case("", 3, vec![Pos(0, 0)]; "everyone at home")]
+    fn houses_with_at_least_two(directions: &str, agents: usize, expected: Vec<Pos>) {
+        let map = DeliveryMap::new(directions, agents).unwrap();
+        assert_eq!(map.houses_with_at_least(2), expected);
+    }
+
+    #[test]
+    fn trailing_newline() {
+        let map = DeliveryMap::new("^>v<\n", 2).unwrap();
+        assert_eq!(map, DeliveryMap::new("^>v<", 2).unwrap());
+    }
+
+    #[test]
+    fn most_visited_and_bounding_box() {
+        let map = DeliveryMap::new("^>v<^>>", 1).unwrap();
+        assert_eq!(map.most_visited(), Some((Pos(0, 0), 2)));
+        assert_eq!(map.bounding_box(), Some((Pos(0, 0), Pos(2, 1))));
+    }
+
+    #[test]
+    fn same_houses_as_deliver() {
+        let map = DeliveryMap::new("^>v<^^<<v", 3).unwrap();
+        let houses = map.houses_with_at_least(1);
+        let mut expected: Vec<Pos> = santa::deliver("^>v<^^<<v", 3)
+            .unwrap()
+            .into_iter()
+            .collect();
+        expected.sort();
+        assert_eq!(houses, expected);
+    }
+
+    #[test]
+    fn ascii() {
+        let map = DeliveryMap::new("^^>vv", 1).unwrap();
+        assert_eq!(map.to_ascii(), "11\n11\n11\n");
+        let map = DeliveryMap::new("^v^v^v^v^v^v^v^v^v^v>", 1).unwrap();
+        assert_eq!(map.to_ascii(), "+.\n+1\n");
+    }
+
+    #[test]
+    fn ppm() {
+        let map = DeliveryMap::new("^>", 2).unwrap();
+        let ppm = map.to_ppm();
+        let header = b"P6\n2 2\n255\n";
+        assert_eq!(&ppm[..header.len()], header);
+        let pixels = &ppm[header.len()..];
+        assert_eq!(pixels.len(), 2 * 2 * 3);
+        // North of home for Santa, nothing, then home with the most presents and east
+        // of it for the robot
+        assert_eq!(&pixels[..3], &[64, 16, 16]);
+        assert_eq!(&pixels[3..6], &[0, 0, 0]);
+        assert_eq!(&pixels[6..9], &PALETTE[0]);
+        assert_eq!(&pixels[9..], &[16, 64, 16]);
+    }
+}
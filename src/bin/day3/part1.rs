@@ -1,9 +1,12 @@
-use crate::santa;
+use super::santa;
 use anyhow::Result;
+use common::Answer;
+use std::io::BufRead;
 
-pub fn run() -> Result<()> {
-    let input = common::read_input(3)?;
-    let total_houses = santa::visited_houses(&input)?;
-    println!("Visited houses: {}", total_houses);
-    Ok(())
+pub fn solve(input: &str) -> Result<Answer> {
+    Ok(santa::visited_houses(input)?.into())
+}
+
+pub fn solve_reader(reader: impl BufRead) -> Result<Answer> {
+    Ok(santa::visited_houses_from_reader(reader)?.into())
 }
@@ -0,0 +1,169 @@
+use super::grid::Position;
+use super::santa::{self, Delivery, Pos};
+use anyhow::Result;
+use std::collections::HashMap;
+use std::io::BufRead;
+
+// An agent back on a house it visited before. Steps are the 0-based indexes of the
+// directions, `previous_step` is None when the house is the starting one and the agent
+// had not come back to it yet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Revisit {
+    pub step: usize,
+    pub agent: usize,
+    pub house: Pos,
+    pub previous_step: Option<usize>,
+    // Moves the agent made since it last left the house
+    pub loop_length: usize,
+}
+
+// Two agents on the same house at the same time, other than at the start
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Meeting {
+    pub step: usize,
+    // The agent that just moved, then the one already there
+    pub agents: (usize, usize),
+    pub house: Pos,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PathAnalysis {
+    pub revisits: Vec<Revisit>,
+    pub first_meeting: Option<Meeting>,
+    // Agent that moved and its Manhattan distance from the start after each step
+    pub distances: Vec<(usize, u64)>,
+}
+
+impl PathAnalysis {
+    // Agents take turns, as in `santa::deliver`
+    pub fn new(directions: &str, agents: usize) -> Result<Self> {
+        Self::from_reader(directions.as_bytes(), agents, santa::round_robin(agents))
+    }
+
+    pub fn from_reader(
+        reader: impl BufRead,
+        agents: usize,
+        assign: impl FnMut(usize) -> usize,
+    ) -> Result<Self> {
+        let mut analysis = Self::default();
+        let mut positions = vec![Pos(0, 0); agents];
+        let mut moves = vec![0; agents];
+        // Last time each agent was on a house: the step and how many moves it had made
+        let mut last_visits: HashMap<(usize, Pos), (Option<usize>, usize)> = HashMap::new();
+        santa::walk(reader, agents, assign, |delivery| {
+            let Delivery { step, agent, house } = delivery;
+            let Some(step) = step else {
+                last_visits.insert((agent, house), (None, 0));
+                return;
+            };
+            moves[agent] += 1;
+            positions[agent] = house;
+            if let Some((previous_step, moves_then)) =
+                last_visits.insert((agent, house), (Some(step), moves[agent]))
+            {
+                analysis.revisits.push(Revisit {
+                    step,
+                    agent,
+                    house,
+                    previous_step,
+                    loop_length: moves[agent] - moves_then,
+                });
+            }
+            if analysis.first_meeting.is_none()
+                && let Some(other) = (0..agents).find(|&a| a != agent && positions[a] == house)
+            {
+                analysis.first_meeting = Some(Meeting {
+                    step,
+                    agents: (agent, other),
+                    house,
+                });
+            }
+            analysis
+                .distances
+                .push((agent, house.distance_from_start()));
+        })?;
+        Ok(analysis)
+    }
+
+    // Revisits of the starting house
+    pub fn returns_to_start(&self) -> impl Iterator<Item = &Revisit> {
+        self.revisits
+            .iter()
+            .filter(|revisit| revisit.house == Pos(0, 0))
+    }
+
+    // Distance from the start of `agent` after each of its own moves
+    pub fn distances_of(&self, agent: usize) -> impl Iterator<Item = u64> + '_ {
+        self.distances
+            .iter()
+            .filter(move |&&(a, _)| a == agent)
+            .map(|&(_, distance)| distance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test]
+    fn revisits_and_loops() {
+        let analysis = PathAnalysis::new("^>v<^", 1).unwrap();
+        assert_eq!(
+            analysis.revisits,
+            vec![
+                Revisit {
+                    step: 3,
+                    agent: 0,
+                    house: Pos(0, 0),
+                    previous_step: None,
+                    loop_length: 4,
+                },
+                Revisit {
+                    step: 4,
+                    agent: 0,
+                    house: Pos(0, 1),
+                    previous_step: Some(0),
+                    loop_length: 4,
+                },
+            ]
+        );
+        assert_eq!(analysis.returns_to_start().count(), 1);
+    }
+
+    #[test]
+    fn loops_of_each_agent() {
+        // Santa goes back and forth, the robot keeps going east
+        let analysis = PathAnalysis::new("^>v>^>", 2).unwrap();
+        let loops: Vec<(usize, usize, usize)> = analysis
+            .revisits
+            .iter()
+            .map(|r| (r.step, r.agent, r.loop_length))
+            .collect();
+        assert_eq!(loops, vec![(2, 0, 2), (4, 0, 2)]);
+    }
+
+    #[test_case("^>v<", 2, Some(Meeting { step: 3, agents: (1, 0), house: Pos(0, 0) }); "back home")]
+    #[test_case("^>>^", 2, Some(Meeting { step: 3, agents: (1, 0), house: Pos(1, 1) }); "elsewhere")]
+    #[test_case("^v^v", 2, None; "never")]
+    #[test_case("^v^v", 1, None; "alone")]
+    fn first_meeting(directions: &str, agents: usize, expected: Option<Meeting>) {
+        let analysis = PathAnalysis::new(directions, agents).unwrap();
+        assert_eq!(analysis.first_meeting, expected);
+    }
+
+    #[test]
+    fn distances() {
+        let analysis = PathAnalysis::new("^^<vv>>", 2).unwrap();
+        assert_eq!(
+            analysis.distances,
+            vec![(0, 1), (1, 1), (0, 2), (1, 0), (0, 1), (1, 1), (0, 0)]
+        );
+        assert_eq!(analysis.distances_of(1).collect::<Vec<_>>(), vec![1, 0, 1]);
+    }
+
+    #[test]
+    fn illegal_direction() {
+        assert!(PathAnalysis::new("^x", 1).is_err());
+    }
+}
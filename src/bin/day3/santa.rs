@@ -1,55 +1,156 @@
+use super::alphabet::{FOUR_WAY, MovementAlphabet};
+use super::grid::Position;
 use anyhow::{Result, bail};
+use common::alphabet::Symbol;
+use common::stream::Chars;
 use std::collections::HashSet;
+use std::io::BufRead;
 
-#[derive(Hash, Eq, PartialEq, Debug, Clone, Copy)]
-struct Pos(i32, i32);
-
-impl Pos {
-    fn new_from_direction(self, direction: char) -> Result<Self> {
-        let (mut x, mut y) = (self.0, self.1);
-        match direction {
-            '>' => x += 1,
-            '<' => x -= 1,
-            '^' => y += 1,
-            'v' => y -= 1,
-            ch => bail!("Illegal direction: '{ch}'"),
-        }
-        Ok(Self(x, y))
+#[derive(Hash, Eq, PartialEq, Ord, PartialOrd, Debug, Default, Clone, Copy)]
+pub struct Pos(pub i32, pub i32);
+
+// One present delivered, after following the `step`-th direction (0-based). `step` is None
+// for the presents every agent delivers at the starting house.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Delivery<P = Pos> {
+    pub step: Option<usize>,
+    pub agent: usize,
+    pub house: P,
+}
+
+// The `step`-th direction (0-based) goes to agent `step % agents`, Santa is agent 0
+pub fn round_robin(agents: usize) -> impl Fn(usize) -> usize {
+    move |step| step % agents
+}
+
+// Houses visited by `agents` agents taking turns at following the directions. Every agent
+// starts at the same house, which counts as visited.
+pub fn deliver(directions: &str, agents: usize) -> Result<HashSet<Pos>> {
+    deliver_with(directions, agents, round_robin(agents))
+}
+
+// Same as `deliver`, `assign` picks the agent that follows the `step`-th direction
+pub fn deliver_with(
+    directions: &str,
+    agents: usize,
+    assign: impl FnMut(usize) -> usize,
+) -> Result<HashSet<Pos>> {
+    deliver_from_reader(directions.as_bytes(), agents, assign)
+}
+
+// Only the set of visited houses is kept in memory, not the directions. Whitespace such as
+// a trailing newline is skipped.
+pub fn deliver_from_reader(
+    reader: impl BufRead,
+    agents: usize,
+    assign: impl FnMut(usize) -> usize,
+) -> Result<HashSet<Pos>> {
+    deliver_on(reader, agents, assign, &MovementAlphabet::lenient(FOUR_WAY))
+}
+
+// Same as `deliver_from_reader`, on any grid and reading directions from any alphabet
+pub fn deliver_on<P: Position>(
+    reader: impl BufRead,
+    agents: usize,
+    assign: impl FnMut(usize) -> usize,
+    alphabet: &MovementAlphabet,
+) -> Result<HashSet<P>> {
+    let mut houses = HashSet::new();
+    walk_on(reader, agents, assign, alphabet, |delivery| {
+        houses.insert(delivery.house);
+    })?;
+    Ok(houses)
+}
+
+// Calls `visit` for every present delivered, in order. Whitespace is skipped, as in
+// `deliver_from_reader`.
+pub fn walk(
+    reader: impl BufRead,
+    agents: usize,
+    assign: impl FnMut(usize) -> usize,
+    visit: impl FnMut(Delivery),
+) -> Result<()> {
+    walk_on(
+        reader,
+        agents,
+        assign,
+        &MovementAlphabet::lenient(FOUR_WAY),
+        visit,
+    )
+}
+
+// Ignored characters are not steps, they are never assigned to an agent
+pub fn walk_on<P: Position>(
+    reader: impl BufRead,
+    agents: usize,
+    mut assign: impl FnMut(usize) -> usize,
+    alphabet: &MovementAlphabet,
+    mut visit: impl FnMut(Delivery<P>),
+) -> Result<()> {
+    if agents == 0 {
+        bail!("At least one agent is needed to deliver presents");
+    }
+    let mut chars = Chars::new(reader);
+    let mut positions = vec![P::default(); agents];
+    for agent in 0..agents {
+        visit(Delivery {
+            step: None,
+            agent,
+            house: P::default(),
+        });
     }
+    let mut step = 0;
+    while let Some(direction) = chars.next().transpose()? {
+        let (dx, dy) = match alphabet.symbol(direction) {
+            Some(Symbol::Move(to)) => to,
+            Some(Symbol::Ignore) => continue,
+            None => {
+                return Err(chars
+                    .error(format!("Illegal direction: {direction:?}"))
+                    .into());
+            }
+        };
+        let agent = assign(step);
+        let Some(house) = positions.get_mut(agent) else {
+            bail!("Step {step} assigned to agent {agent}, but there are only {agents} agent(s)");
+        };
+        *house = house
+            .moved(dx, dy)
+            .ok_or_else(|| chars.error("Position out of range"))?;
+        visit(Delivery {
+            step: Some(step),
+            agent,
+            house: *house,
+        });
+        step += 1;
+    }
+    Ok(())
 }
 
 pub fn visited_houses(directions: &str) -> Result<usize> {
-    let mut houses = HashSet::<Pos>::new();
-    let mut house = Pos(0, 0);
-    houses.insert(house);
-    for direction in directions.chars() {
-        house = house.new_from_direction(direction)?;
-        houses.insert(house);
-    }
-    Ok(houses.len())
+    Ok(deliver(directions, 1)?.len())
+}
+
+pub fn visited_houses_from_reader(reader: impl BufRead) -> Result<usize> {
+    Ok(deliver_from_reader(reader, 1, round_robin(1))?.len())
 }
 
 pub fn visited_houses_with_robot(directions: &str) -> Result<usize> {
-    let mut houses = HashSet::<Pos>::new();
-    let mut santa_house = Pos(0, 0);
-    let mut robot_house = Pos(0, 0);
-    houses.insert(santa_house);
-    houses.insert(robot_house);
-    for (i, direction) in directions.chars().enumerate() {
-        if i % 2 == 0 {
-            santa_house = santa_house.new_from_direction(direction)?;
-            houses.insert(santa_house);
-        } else {
-            robot_house = robot_house.new_from_direction(direction)?;
-            houses.insert(robot_house);
-        }
-    }
-    Ok(houses.len())
+    Ok(deliver(directions, 2)?.len())
+}
+
+pub fn visited_houses_with_robot_from_reader(reader: impl BufRead) -> Result<usize> {
+    Ok(deliver_from_reader(reader, 2, round_robin(2))?.len())
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Pos;
+    use super::super::alphabet::{DIAGONALS, FOUR_WAY, HEX, MovementAlphabet, STAY};
+    use super::super::grid::{HexPos, Position};
+    use super::{Pos, round_robin};
+    use common::alphabet::Symbol;
+    use proptest::prelude::*;
+    use std::collections::HashSet;
     use test_case::test_case;
 
     #[test_case('>', Pos(1, 0); "move right")]
@@ -57,9 +158,18 @@ mod tests {
     #[test_case('^', Pos(0, 1); "move up")]
     #[test_case('v', Pos(0, -1); "move down")]
     fn new_house(direction: char, expected: Pos) {
-        let start = Pos(0, 0);
-        let end = start.new_from_direction(direction).unwrap();
-        assert_eq!(end, expected);
+        let mut houses: Vec<Pos> = Vec::new();
+        let alphabet = MovementAlphabet::new(FOUR_WAY);
+        let directions = direction.to_string();
+        super::walk_on(
+            directions.as_bytes(),
+            1,
+            round_robin(1),
+            &alphabet,
+            |delivery| houses.push(delivery.house),
+        )
+        .unwrap();
+        assert_eq!(houses, vec![Pos(0, 0), expected]);
     }
 
     #[test_case(">", 2; "t1")]
@@ -77,4 +187,141 @@ mod tests {
         let result = super::visited_houses_with_robot(directions).unwrap();
         assert_eq!(result, expected);
     }
+
+    #[test_case("^>v<", 1, 4; "alone")]
+    #[test_case("^>v<", 2, 3; "with robot")]
+    #[test_case("^>v<", 4, 5; "one step each")]
+    #[test_case(">>>>>>", 3, 3; "three in a row")]
+    #[test_case("", 5, 1; "nothing to do")]
+    fn deliver(directions: &str, agents: usize, expected: usize) {
+        let houses = super::deliver(directions, agents).unwrap();
+        assert_eq!(houses.len(), expected);
+    }
+
+    #[test]
+    fn deliver_with_assignment() {
+        // Santa takes the first half, the robot the rest
+        let houses = super::deliver_with("^^^vvv", 2, |step| step / 3).unwrap();
+        assert_eq!(
+            houses,
+            HashSet::from([
+                Pos(0, 0),
+                Pos(0, 1),
+                Pos(0, 2),
+                Pos(0, 3),
+                Pos(0, -1),
+                Pos(0, -2),
+                Pos(0, -3)
+            ])
+        );
+    }
+
+    #[test]
+    fn deliver_errors() {
+        assert!(super::deliver("^", 0).is_err());
+        assert!(super::deliver_with("^v", 2, |step| step * 2).is_err());
+    }
+
+    #[test_case("^>v<\n", MovementAlphabet::lenient(FOUR_WAY), 1, 4; "trailing newline")]
+    #[test_case("^ > v <", MovementAlphabet::lenient(FOUR_WAY), 2, 3; "whitespace is not a step")]
+    #[test_case("↗↗↙", MovementAlphabet::new(FOUR_WAY).with_moves(DIAGONALS), 1, 3; "diagonals")]
+    #[test_case("^.^.", MovementAlphabet::new(FOUR_WAY).with_move('.', STAY), 2, 3; "robot stays home")]
+    fn deliver_on(directions: &str, alphabet: MovementAlphabet, agents: usize, expected: usize) {
+        let houses: HashSet<Pos> = super::deliver_on(
+            directions.as_bytes(),
+            agents,
+            round_robin(agents),
+            &alphabet,
+        )
+        .unwrap();
+        assert_eq!(houses.len(), expected);
+    }
+
+    #[test]
+    fn deliver_on_hex_grid() {
+        // Around every neighbour of the start
+        let houses: HashSet<HexPos> = super::deliver_on(
+            ">↖<↙↘>".as_bytes(),
+            1,
+            round_robin(1),
+            &MovementAlphabet::new(HEX),
+        )
+        .unwrap();
+        assert_eq!(houses.len(), 7);
+        assert!(houses.iter().all(|house| house.distance_from_start() <= 1));
+        assert!(
+            super::deliver_on::<HexPos>(
+                "^".as_bytes(),
+                1,
+                round_robin(1),
+                &MovementAlphabet::new(HEX)
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn from_reader() {
+        let reader = std::io::BufReader::with_capacity(2, "^>v<".as_bytes());
+        assert_eq!(super::visited_houses_from_reader(reader).unwrap(), 4);
+        let reader = std::io::BufReader::with_capacity(2, "^>v<".as_bytes());
+        assert_eq!(
+            super::visited_houses_with_robot_from_reader(reader).unwrap(),
+            3
+        );
+    }
+
+    #[test]
+    fn illegal_direction() {
+        let err = super::visited_houses("^>v<x").unwrap_err();
+        let err = err.downcast::<common::ParseError>().unwrap();
+        assert_eq!(err.line, Some(1));
+        assert_eq!(err.span, 4..5);
+    }
+
+    // One step of the original loops, straight from the puzzle's four directions
+    fn step(house: Pos, direction: char) -> Pos {
+        let Some(Symbol::Move((dx, dy))) = MovementAlphabet::new(FOUR_WAY).symbol(direction) else {
+            panic!("Illegal direction: {direction:?}");
+        };
+        house.moved(dx, dy).unwrap()
+    }
+
+    // The original loops, before any number of agents could deliver
+    fn santa_alone(directions: &str) -> usize {
+        let mut santa = Pos(0, 0);
+        let mut houses = HashSet::from([santa]);
+        for direction in directions.chars() {
+            santa = step(santa, direction);
+            houses.insert(santa);
+        }
+        houses.len()
+    }
+
+    fn santa_and_robot(directions: &str) -> usize {
+        let (mut santa, mut robot) = (Pos(0, 0), Pos(0, 0));
+        let mut houses = HashSet::from([santa]);
+        for (i, direction) in directions.chars().enumerate() {
+            let mover = if i % 2 == 0 { &mut santa } else { &mut robot };
+            *mover = step(*mover, direction);
+            houses.insert(*mover);
+        }
+        houses.len()
+    }
+
+    proptest! {
+        #[test]
+        fn never_panics(s in "[<>^v\n]{0,40}|\\PC*") {
+            let _ = super::visited_houses(&s);
+            let _ = super::visited_houses_with_robot(&s);
+        }
+
+        #[test]
+        fn round_robin_matches_the_originals(s in "[<>^v]{0,40}") {
+            prop_assert_eq!(super::deliver(&s, 1).unwrap().len(), santa_alone(&s));
+            prop_assert_eq!(super::visited_houses(&s).unwrap(), santa_alone(&s));
+            prop_assert_eq!(super::deliver(&s, 2).unwrap().len(), santa_and_robot(&s));
+            prop_assert_eq!(super::visited_houses_with_robot(&s).unwrap(), santa_and_robot(&s));
+        }
+    }
 }
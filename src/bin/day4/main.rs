@@ -1,10 +1,28 @@
+mod miner;
 mod part1;
 mod part2;
 mod password;
 
 use anyhow::Result;
+use common::registry::{self, Registry};
+use common::{Answer, Solution};
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    fn part1(&self, input: &str) -> Result<Answer> {
+        part1::solve(input)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        part2::solve(input)
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(4, Day4);
+}
+
 fn main() -> Result<()> {
-    part1::run()?;
-    part2::run()?;
-    Ok(())
+    registry::main_for(register)
 }
@@ -0,0 +1,201 @@
+use super::password::Difficulty;
+use anyhow::{Result, bail};
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+
+// Nonces a thread takes at a time
+const BATCH: u64 = 1 << 12;
+// Decimal digits of `n` at the end of `buf`, without allocating
+fn decimal(mut n: u64, buf: &mut [u8; 20]) -> &[u8] {
+    let mut start = buf.len();
+    loop {
+        start -= 1;
+        buf[start] = b'0' + (n % 10) as u8;
+        n /= 10;
+        if n == 0 {
+            return &buf[start..];
+        }
+    }
+}
+
+// Searches nonces for a door id on every core. The MD5 state after the door id is computed
+// once and only the nonce digits are hashed for each candidate.
+#[derive(Clone)]
+pub struct Miner {
+    prefix: md5::Context,
+    threads: usize,
+    batch: u64,
+}
+
+impl Miner {
+    pub fn new(door_id: &str) -> Self {
+        let mut prefix = md5::Context::new();
+        prefix.consume(door_id.trim());
+        Self {
+            prefix,
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+            batch: BATCH,
+        }
+    }
+
+    // Tuning the threads and batches is only needed by the tests
+    #[allow(dead_code)]
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn with_batch(mut self, batch: u64) -> Self {
+        self.batch = batch.max(1);
+        self
+    }
+
+    pub fn digest(&self, nonce: u64) -> [u8; 16] {
+        let mut buf = [0; 20];
+        let mut context = self.prefix.clone();
+        context.consume(decimal(nonce, &mut buf));
+        context.finalize().0
+    }
+
+    pub fn lowest(&self, difficulty: &Difficulty) -> Result<u64> {
+        Ok(self.first(difficulty, 1)?[0])
+    }
+
+    // The `k` lowest nonces meeting `difficulty`, in increasing order. Threads take batches
+    // of nonces in increasing order and stop once a batch starts past the k-th lowest match
+    // found so far. Every batch below it is searched whole, so the result does not depend
+    // on the number of threads.
+    pub fn first(&self, difficulty: &Difficulty, k: usize) -> Result<Vec<u64>> {
+        difficulty.check()?;
+        if k == 0 {
+            return Ok(Vec::new());
+        }
+        let next_batch = AtomicU64::new(0);
+        // Nonces past the k-th lowest match are of no use, u64::MAX until k are found
+        let bound = AtomicU64::new(u64::MAX);
+        let found = Mutex::new(BTreeSet::new());
+        thread::scope(|scope| {
+            for _ in 0..self.threads {
+                scope.spawn(|| {
+                    loop {
+                        let start = next_batch.fetch_add(self.batch, Ordering::Relaxed);
+                        if start >= bound.load(Ordering::Relaxed) {
+                            break;
+                        }
+                        let end = start.saturating_add(self.batch);
+                        let hits: Vec<u64> = (start..end)
+                            .take_while(|&nonce| nonce < bound.load(Ordering::Relaxed))
+                            .filter(|&nonce| difficulty.is_met(&self.digest(nonce)))
+                            .collect();
+                        if hits.is_empty() {
+                            continue;
+                        }
+                        let mut found = found.lock().unwrap();
+                        found.extend(hits);
+                        while found.len() > k {
+                            found.pop_last();
+                        }
+                        if found.len() == k {
+                            bound.fetch_min(*found.last().unwrap(), Ordering::Relaxed);
+                        }
+                    }
+                });
+            }
+        });
+        let found: Vec<u64> = found.into_inner().unwrap().into_iter().collect();
+        if found.len() < k {
+            bail!("Only {} nonce(s) found meeting {difficulty:?}", found.len());
+        }
+        Ok(found)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    // The straightforward search, one nonce after the other
+    fn first_sequential(door_id: &str, zeros: usize, k: usize) -> Vec<u64> {
+        (0..)
+            .filter(|nonce| {
+                format!("{:x}", md5::compute(format!("{door_id}{nonce}")))
+                    .starts_with(&"0".repeat(zeros))
+            })
+            .take(k)
+            .collect()
+    }
+
+    #[test_case(0, "0"; "zero")]
+    #[test_case(609043, "609043"; "puzzle")]
+    #[test_case(u64::MAX, "18446744073709551615"; "largest")]
+    fn decimal_digits(n: u64, expected: &str) {
+        let mut buf = [0; 20];
+        assert_eq!(decimal(n, &mut buf), expected.as_bytes());
+    }
+
+    #[test]
+    fn digest_reuses_the_prefix() {
+        let miner = Miner::new("abcdef\n");
+        assert_eq!(miner.digest(609043), md5::compute("abcdef609043").0);
+    }
+
+    #[test_case("abcdef", 2; "abcdef")]
+    #[test_case("pqrstuv", 3; "pqrstuv")]
+    #[test_case("iwrupvqb", 3; "input")]
+    fn same_as_sequential(door_id: &str, zeros: u32) {
+        let expected = first_sequential(door_id, zeros as usize, 5);
+        let difficulty = Difficulty::LeadingZeroNibbles(zeros);
+        for (threads, batch) in [(1, 1), (1, 4096), (4, 1), (4, 7), (8, 64)] {
+            let miner = Miner::new(door_id).with_threads(threads).with_batch(batch);
+            assert_eq!(miner.lowest(&difficulty).unwrap(), expected[0]);
+            assert_eq!(miner.first(&difficulty, 5).unwrap(), expected);
+        }
+    }
+
+    #[test_case("abcdef", 609043; "t1")]
+    #[test_case("pqrstuv", 1048970; "t2")]
+    fn five_zeros(door_id: &str, expected: u64) {
+        let difficulty = Difficulty::LeadingZeroNibbles(5);
+        assert_eq!(Miner::new(door_id).lowest(&difficulty).unwrap(), expected);
+    }
+
+    #[test]
+    fn first_none() {
+        let difficulty = Difficulty::LeadingZeroNibbles(30);
+        assert_eq!(
+            Miner::new("abcdef").first(&difficulty, 0).unwrap(),
+            Vec::<u64>::new()
+        );
+    }
+
+    #[test]
+    fn other_difficulties() {
+        let miner = Miner::new("abcdef");
+        // Twelve zero bits are three zero nibbles, the threshold keeps the top 12 bits at 0
+        let nibbles = miner.lowest(&Difficulty::LeadingZeroNibbles(3)).unwrap();
+        assert_eq!(
+            miner.lowest(&Difficulty::LeadingZeroBits(12)).unwrap(),
+            nibbles
+        );
+        assert_eq!(miner.lowest(&Difficulty::Below(1 << 116)).unwrap(), nibbles);
+        let prefix = Difficulty::hex_prefix("abc").unwrap();
+        let nonce = miner.lowest(&prefix).unwrap();
+        assert!(format!("{:x}", md5::compute(format!("abcdef{nonce}"))).starts_with("abc"));
+        let custom = Difficulty::custom(|digest| digest[15] == 0x42);
+        let nonce = miner.lowest(&custom).unwrap();
+        assert_eq!(miner.digest(nonce)[15], 0x42);
+    }
+
+    #[test]
+    fn impossible_difficulty() {
+        assert!(
+            Miner::new("abcdef")
+                .lowest(&Difficulty::LeadingZeroNibbles(33))
+                .is_err()
+        );
+    }
+}
@@ -1,9 +1,8 @@
-use crate::password;
+use super::password;
 use anyhow::Result;
+use common::Answer;
 
-pub fn run() -> Result<()> {
-    let input = common::read_input(4)?;
-    let result = password::lowest_number_with_n_leading_zeros(&input, 5);
-    println!("Valid passwords: {}", result);
-    Ok(())
+pub fn solve(input: &str) -> Result<Answer> {
+    let result = password::lowest_number_with_n_leading_zeros(input, 5)?;
+    Ok(result.into())
 }
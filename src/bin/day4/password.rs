@@ -1,18 +1,116 @@
-pub fn lowest_number_with_n_leading_zeros(door_id: &str, n: usize) -> usize {
-    for i in 0.. {
-        let input = format!("{}{}", door_id, i);
-        let digest = md5::compute(input);
-        let hash_string = format!("{:x}", digest);
-        let zeroes = "0".repeat(n);
-        if hash_string.starts_with(&zeroes) {
-            return i;
+use super::miner::Miner;
+use anyhow::{Result, bail};
+use std::fmt::Debug;
+use std::sync::Arc;
+
+// Bits in an MD5 digest
+const DIGEST_BITS: u32 = 128;
+
+type Predicate = Arc<dyn Fn(&[u8; 16]) -> bool + Send + Sync>;
+
+// What a digest must look like for its nonce to mine an AdventCoin
+#[derive(Clone)]
+pub enum Difficulty {
+    // The puzzle's: the hex form of the digest starts with that many '0'
+    LeadingZeroNibbles(u32),
+    // The other difficulties are not needed by the two parts
+    #[allow(dead_code)]
+    LeadingZeroBits(u32),
+    // Lowercase hex digits the hex form of the digest starts with, see `hex_prefix`
+    #[allow(dead_code)]
+    HexPrefix(Vec<u8>),
+    // The digest, read as a big-endian number, is below the threshold
+    #[allow(dead_code)]
+    Below(u128),
+    #[allow(dead_code)]
+    Custom(Predicate),
+}
+
+impl Debug for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Difficulty::LeadingZeroNibbles(n) => write!(f, "LeadingZeroNibbles({n})"),
+            Difficulty::LeadingZeroBits(n) => write!(f, "LeadingZeroBits({n})"),
+            Difficulty::HexPrefix(nibbles) => {
+                let prefix: String = nibbles.iter().map(|&n| format!("{n:x}")).collect();
+                write!(f, "HexPrefix({prefix:?})")
+            }
+            Difficulty::Below(threshold) => write!(f, "Below({threshold:#x})"),
+            Difficulty::Custom(_) => write!(f, "Custom(..)"),
+        }
+    }
+}
+
+impl Difficulty {
+    #[allow(dead_code)]
+    pub fn hex_prefix(prefix: &str) -> Result<Self> {
+        let nibbles = prefix
+            .chars()
+            .map(|ch| match ch.to_digit(16) {
+                Some(n) if !ch.is_ascii_uppercase() => Ok(n as u8),
+                _ => bail!("Bad hex digit {ch:?} in prefix {prefix:?}"),
+            })
+            .collect::<Result<_>>()?;
+        Ok(Difficulty::HexPrefix(nibbles))
+    }
+
+    #[allow(dead_code)]
+    pub fn custom(predicate: impl Fn(&[u8; 16]) -> bool + Send + Sync + 'static) -> Self {
+        Difficulty::Custom(Arc::new(predicate))
+    }
+
+    pub fn is_met(&self, digest: &[u8; 16]) -> bool {
+        let value = u128::from_be_bytes(*digest);
+        match self {
+            Difficulty::LeadingZeroNibbles(n) => value.leading_zeros() >= n.saturating_mul(4),
+            Difficulty::LeadingZeroBits(n) => value.leading_zeros() >= *n,
+            Difficulty::HexPrefix(nibbles) => {
+                nibbles.len() <= 32
+                    && nibbles.iter().enumerate().all(|(i, &n)| {
+                        (value >> (DIGEST_BITS - 4 * (i as u32 + 1))) as u8 & 0xf == n
+                    })
+            }
+            Difficulty::Below(threshold) => value < *threshold,
+            Difficulty::Custom(predicate) => predicate(digest),
+        }
+    }
+
+    // Rules out what no digest can meet, the search would never end. Custom predicates
+    // cannot be checked.
+    pub fn check(&self) -> Result<()> {
+        match self {
+            Difficulty::LeadingZeroNibbles(n) if n.saturating_mul(4) > DIGEST_BITS => {
+                bail!("An MD5 digest has only 32 hex digits, {n} leading zeros asked")
+            }
+            Difficulty::LeadingZeroBits(n) if *n > DIGEST_BITS => {
+                bail!("An MD5 digest has only {DIGEST_BITS} bits, {n} leading zeros asked")
+            }
+            Difficulty::HexPrefix(nibbles) if nibbles.len() > 32 => {
+                bail!(
+                    "An MD5 digest has only 32 hex digits, a prefix of {} asked",
+                    nibbles.len()
+                )
+            }
+            Difficulty::Below(0) => bail!("No digest is below 0"),
+            _ => Ok(()),
         }
     }
-    unreachable!()
+}
+
+pub fn lowest_number_with_n_leading_zeros(door_id: &str, n: u32) -> Result<u64> {
+    Miner::new(door_id).lowest(&Difficulty::LeadingZeroNibbles(n))
+}
+
+// The `k` lowest nonces meeting `difficulty`, in increasing order. Not needed by the two
+// parts, which only ask for the lowest.
+#[allow(dead_code)]
+pub fn first_nonces(door_id: &str, difficulty: &Difficulty, k: usize) -> Result<Vec<u64>> {
+    Miner::new(door_id).first(difficulty, k)
 }
 
 #[cfg(test)]
 mod tests {
+    use super::Difficulty;
     use test_case::test_case;
 
     #[test_case("abcdef609043", "000001dbbfa3a5c83a2d506429c7b00e"; "t1")]
@@ -24,8 +122,77 @@ mod tests {
 
     #[test_case("abcdef", 5, 609043; "t1")]
     #[test_case("pqrstuv", 5, 1048970; "t2")]
-    fn lowest_number_with_n_leading_zeros(input: &str, zeroes: usize, expected: usize) {
-        let result = super::lowest_number_with_n_leading_zeros(input, zeroes);
+    fn lowest_number_with_n_leading_zeros(input: &str, zeroes: u32, expected: u64) {
+        let result = super::lowest_number_with_n_leading_zeros(input, zeroes).unwrap();
         assert_eq!(result, expected);
     }
+
+    fn digest(start: &[u8]) -> [u8; 16] {
+        let mut digest = [0xff; 16];
+        digest[..start.len()].copy_from_slice(start);
+        digest
+    }
+
+    #[test_case(Difficulty::LeadingZeroNibbles(5), &[0x00, 0x00, 0x0f], true; "five nibbles")]
+    #[test_case(Difficulty::LeadingZeroNibbles(5), &[0x00, 0x00, 0x1f], false; "four nibbles")]
+    #[test_case(Difficulty::LeadingZeroNibbles(0), &[0xff], true; "no nibble")]
+    #[test_case(Difficulty::LeadingZeroNibbles(33), &[0; 16], false; "more nibbles than a digest")]
+    #[test_case(Difficulty::LeadingZeroBits(19), &[0x00, 0x00, 0x1f], true; "nineteen bits")]
+    #[test_case(Difficulty::LeadingZeroBits(20), &[0x00, 0x00, 0x1f], false; "twenty bits")]
+    #[test_case(Difficulty::LeadingZeroBits(128), &[0; 16], true; "every bit")]
+    #[test_case(Difficulty::hex_prefix("00a").unwrap(), &[0x00, 0xa5], true; "hex prefix")]
+    #[test_case(Difficulty::hex_prefix("00a").unwrap(), &[0x00, 0x5a], false; "other hex prefix")]
+    #[test_case(Difficulty::hex_prefix("").unwrap(), &[0x12], true; "empty prefix")]
+    #[test_case(Difficulty::Below(1 << 120), &[0x00, 0xff], true; "below")]
+    #[test_case(Difficulty::Below(1 << 120), &[0x01], false; "at the threshold")]
+    #[test_case(Difficulty::custom(|d| d[0] == 0x42), &[0x42], true; "custom")]
+    fn is_met(difficulty: Difficulty, start: &[u8], expected: bool) {
+        assert_eq!(difficulty.is_met(&digest(start)), expected);
+    }
+
+    #[test_case("0G"; "not hex")]
+    #[test_case("0A"; "uppercase")]
+    fn bad_hex_prefix(prefix: &str) {
+        assert!(Difficulty::hex_prefix(prefix).is_err());
+    }
+
+    #[test_case(Difficulty::LeadingZeroNibbles(33); "nibbles")]
+    #[test_case(Difficulty::LeadingZeroBits(129); "bits")]
+    #[test_case(Difficulty::hex_prefix(&"0".repeat(33)).unwrap(); "prefix")]
+    #[test_case(Difficulty::Below(0); "threshold")]
+    fn impossible(difficulty: Difficulty) {
+        assert!(difficulty.check().is_err());
+    }
+
+    #[test]
+    fn nibbles_are_bits() {
+        // Any digest meets both or neither
+        for nonce in 0..2000 {
+            let digest = md5::compute(format!("abcdef{nonce}")).0;
+            for n in 0..4 {
+                assert_eq!(
+                    Difficulty::LeadingZeroNibbles(n).is_met(&digest),
+                    Difficulty::LeadingZeroBits(4 * n).is_met(&digest)
+                );
+                assert_eq!(
+                    Difficulty::LeadingZeroNibbles(n).is_met(&digest),
+                    Difficulty::hex_prefix(&"0".repeat(n as usize))
+                        .unwrap()
+                        .is_met(&digest)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn first_nonces() {
+        let difficulty = Difficulty::LeadingZeroNibbles(3);
+        let nonces = super::first_nonces("abcdef", &difficulty, 3).unwrap();
+        assert_eq!(nonces.len(), 3);
+        assert!(nonces.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(
+            nonces[0],
+            super::lowest_number_with_n_leading_zeros("abcdef", 3).unwrap()
+        );
+    }
 }
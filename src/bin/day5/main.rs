@@ -1,8 +1,39 @@
 mod part1;
 mod part2;
+
 use anyhow::Result;
+use common::registry::{self, Registry};
+use common::{Answer, Solution};
+use std::io::BufRead;
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    fn part1(&self, input: &str) -> Result<Answer> {
+        part1::solve(input)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        part2::solve(input)
+    }
+
+    fn streams(&self) -> bool {
+        true
+    }
+
+    fn part1_from_reader(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        part1::solve_reader(reader)
+    }
+
+    fn part2_from_reader(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        part2::solve_reader(reader)
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(5, Day5);
+}
+
 fn main() -> Result<()> {
-    part1::run()?;
-    part2::run()?;
-    Ok(())
+    registry::main_for(register)
 }
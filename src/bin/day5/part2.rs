@@ -1,4 +1,7 @@
 use anyhow::Result;
+use common::Answer;
+use common::stream;
+use std::io::BufRead;
 
 fn is_nice(s: &str) -> bool {
     // Rule 1: it contains a pair of any two letters that appears at least twice in the string without overlapping
@@ -20,11 +23,24 @@ fn is_nice(s: &str) -> bool {
     true
 }
 
-pub fn run() -> Result<()> {
-    let input = common::read_input(5)?;
-    let count = input.lines().filter(|line| is_nice(line)).count();
-    println!("Number of 'nice' strings: {count}");
-    Ok(())
+// Strings are checked one line at a time as they are read
+pub fn count_nice(reader: impl BufRead) -> Result<usize> {
+    let mut count = 0;
+    for nice in stream::parse_lines(reader, |line| Ok(is_nice(line))) {
+        if nice? {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+pub fn solve(input: &str) -> Result<Answer> {
+    solve_reader(input.as_bytes())
+}
+
+pub fn solve_reader(reader: impl BufRead) -> Result<Answer> {
+    let count = count_nice(reader)?;
+    Ok(count.into())
 }
 
 #[cfg(test)]
@@ -39,4 +55,13 @@ mod tests {
         let result = super::is_nice(input);
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn count_nice() {
+        let reader = std::io::BufReader::with_capacity(
+            4,
+            "qjhvhtzxzqqjkmpb\nxxyxx\nuurcxstgmygtbstg\n\nieodomkazucvgmuy\n".as_bytes(),
+        );
+        assert_eq!(super::count_nice(reader).unwrap(), 2);
+    }
 }
@@ -1,4 +1,4 @@
-use anyhow::{Result, bail};
+use common::ParseError;
 use itertools::Itertools;
 
 const GRID_SIZE: usize = 1000;
@@ -7,24 +7,37 @@ struct Pos(usize, usize);
 
 impl Pos {
     // Constructs a Pos from a string like "0,0"
-    fn from_coords(str_pos: &str) -> Result<Self> {
+    fn from_coords(str_pos: &str) -> Result<Self, ParseError> {
         let parts: Vec<usize> = str_pos
             .split(',')
-            .map(|s| s.parse::<usize>().map(|v| v))
+            .map(|s| match s.parse::<usize>() {
+                Ok(coord) if coord < GRID_SIZE => Ok(coord),
+                Ok(_) => Err(ParseError::at(
+                    str_pos,
+                    s,
+                    format!("Coordinate '{s}' is outside the grid"),
+                )),
+                Err(_) => Err(ParseError::at(str_pos, s, format!("Bad coordinate '{s}'"))),
+            })
             .try_collect()?;
         if parts.len() == 2 {
             Ok(Pos(parts[0], parts[1]))
         } else {
-            bail!(r#"Error parsing Pos from: "{str_pos}" "#)
+            Err(ParseError::new(
+                str_pos,
+                0..str_pos.len(),
+                format!(r#"Error parsing Pos from: "{str_pos}""#),
+            ))
         }
     }
 }
 struct Rect(Pos, Pos);
 
 impl Rect {
-    fn from_coords(start: &str, end: &str) -> Result<Self> {
-        let top_left = Pos::from_coords(start)?;
-        let bottom_right = Pos::from_coords(end)?;
+    // `start` and `end` are slices of `line`, so that errors point into the whole line
+    fn from_coords(line: &str, start: &str, end: &str) -> Result<Self, ParseError> {
+        let top_left = Pos::from_coords(start).map_err(|err| err.within(line, start))?;
+        let bottom_right = Pos::from_coords(end).map_err(|err| err.within(line, end))?;
         Ok(Rect(top_left, bottom_right))
     }
 }
@@ -41,32 +54,52 @@ impl Grid {
         }
     }
 
-    pub fn apply_action(&mut self, s: &str) -> Result<()> {
+    pub fn apply_action(&mut self, s: &str) -> Result<(), ParseError> {
         // Allow no action when s is empty (test only!)
         if cfg!(test) && s.is_empty() {
             return Ok(());
         }
         let parts: Vec<&str> = s.split_whitespace().collect();
-        match parts[0] {
-            "turn" => match parts[1] {
-                "on" => {
-                    let rect = Rect::from_coords(parts[2], parts[4])?;
-                    self.lights_on(rect);
-                }
-                "off" => {
-                    let rect = Rect::from_coords(parts[2], parts[4])?;
-                    self.lights_off(rect);
-                }
-                wtf => {
-                    bail!("Expected 'on' or 'off' instead of '{wtf}'");
-                }
-            },
-            "toggle" => {
-                let rect = Rect::from_coords(parts[1], parts[3])?;
+        let rect = |coords: &[&str]| match coords {
+            [start, "through", end] => Rect::from_coords(s, start, end),
+            _ => Err(ParseError::at(
+                s,
+                s.trim(),
+                "Expected coordinates like '0,0 through 999,999'",
+            )),
+        };
+        match parts.as_slice() {
+            ["turn", "on", coords @ ..] => {
+                let rect = rect(coords)?;
+                self.lights_on(rect);
+            }
+            ["turn", "off", coords @ ..] => {
+                let rect = rect(coords)?;
+                self.lights_off(rect);
+            }
+            ["turn", wtf, ..] => {
+                return Err(ParseError::at(
+                    s,
+                    wtf,
+                    format!("Expected 'on' or 'off' instead of '{wtf}'"),
+                ));
+            }
+            ["turn"] => {
+                return Err(ParseError::at_end(s, "Expected 'on' or 'off'"));
+            }
+            ["toggle", coords @ ..] => {
+                let rect = rect(coords)?;
                 self.toggle_lights(rect);
             }
-            wtf => {
-                bail!("Expected 'turn' or 'toggle' instead of '{wtf}'");
+            [wtf, ..] => {
+                return Err(ParseError::at(
+                    s,
+                    wtf,
+                    format!("Expected 'turn' or 'toggle' instead of '{wtf}'"),
+                ));
+            }
+            [] => {
+                return Err(ParseError::at_end(s, "Expected 'turn' or 'toggle'"));
             }
         }
         Ok(())
@@ -128,6 +161,7 @@ impl Grid {
 #[cfg(test)]
 mod tests {
     use super::{Grid, Pos, Rect};
+    use proptest::prelude::*;
     use test_case::test_case;
 
     #[test]
@@ -139,7 +173,8 @@ mod tests {
 
     #[test]
     fn rect_from_coords() {
-        let rect = Rect::from_coords("0,0", "999,999").unwrap();
+        let line = "0,0 through 999,999";
+        let rect = Rect::from_coords(line, &line[..3], &line[12..]).unwrap();
         assert_eq!(rect.0.0, 0);
         assert_eq!(rect.0.1, 0);
         assert_eq!(rect.1.0, 999);
@@ -179,4 +214,28 @@ mod tests {
         grid.apply_action(action).unwrap();
         assert_eq!(grid.sum_brightness(), expected);
     }
+
+    #[test_case("turn on 0,0 through 9x9,5", 20..23; "bad coordinate")]
+    #[test_case("turn off 0,0 through 1000,5", 21..25; "outside the grid")]
+    #[test_case("turn sideways 0,0 through 1,1", 5..13; "bad turn")]
+    #[test_case("switch 0,0 through 1,1", 0..6; "bad action")]
+    #[test_case("toggle 0,0 to 1,1", 0..17; "missing through")]
+    fn bad_actions(action: &str, span: std::ops::Range<usize>) {
+        let mut grid = Grid::new(false);
+        let err = grid.apply_action(action).unwrap_err();
+        assert_eq!(err.text, action);
+        assert_eq!(err.span, span);
+    }
+
+    proptest! {
+        // Every valid action touches up to a million lights, keep the number of cases low
+        #![proptest_config(ProptestConfig::with_cases(64))]
+        #[test]
+        fn apply_action_never_panics(
+            s in "(turn |toggle |switch )?(on |off )?[0-9]{0,4},?[0-9]{0,4}( through)? ?[0-9]{0,4},?[0-9]{0,4}|\\PC*"
+        ) {
+            let mut grid = Grid::new(false);
+            let _ = grid.apply_action(&s);
+        }
+    }
 }
@@ -1,13 +1,13 @@
-use crate::grid::Grid;
+use super::grid::Grid;
 use anyhow::Result;
+use common::Answer;
 
-pub fn run() -> Result<()> {
-    let input = common::read_input(6)?;
+pub fn solve(input: &str) -> Result<Answer> {
     let mut grid = Grid::new(false);
-    for line in input.lines() {
-        grid.apply_action(line)?;
+    for (i, line) in input.lines().enumerate() {
+        grid.apply_action(line)
+            .map_err(|err| err.with_line(i + 1))?;
     }
     let count = grid.count_lights_on();
-    println!("Number of lights on: {count}");
-    Ok(())
+    Ok(count.into())
 }
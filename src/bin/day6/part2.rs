@@ -1,13 +1,13 @@
-use crate::grid::Grid;
+use super::grid::Grid;
 use anyhow::Result;
+use common::Answer;
 
-pub fn run() -> Result<()> {
-    let input = common::read_input(6)?;
+pub fn solve(input: &str) -> Result<Answer> {
     let mut grid = Grid::new(true);
-    for line in input.lines() {
-        grid.apply_action(line)?;
+    for (i, line) in input.lines().enumerate() {
+        grid.apply_action(line)
+            .map_err(|err| err.with_line(i + 1))?;
     }
     let total_brightness = grid.sum_brightness();
-    println!("Total brightness: {total_brightness}");
-    Ok(())
+    Ok(total_brightness.into())
 }
@@ -1,9 +1,13 @@
 use anyhow::{Result, bail};
+use common::ParseError;
+use common::parse_error::parse_lines;
 use std::{
     collections::HashMap,
     fmt::{Debug, Display},
 };
 
+const BINARY_GATES: [&str; 4] = ["AND", "OR", "LSHIFT", "RSHIFT"];
+
 macro_rules! signal_or_wire {
     ($x:expr) => {
         if let Ok(signal) = $x.parse::<u16>() {
@@ -88,7 +92,7 @@ impl Display for Component {
 }
 
 impl Component {
-    pub fn from_str(line: &str) -> Result<Self> {
+    pub fn from_str(line: &str) -> Result<Self, ParseError> {
         let parts: Vec<&str> = line.split_whitespace().collect();
 
         match parts.len() {
@@ -110,12 +114,19 @@ impl Component {
                         right: signal_or_wire!(parts[1]),
                     })
                 } else {
-                    bail!("Expected a NOT gate in '{line}'");
+                    Err(ParseError::at(line, parts[0], "Expected a NOT gate"))
                 }
             }
 
             3 => {
                 // When length is 3, it must be a binary gate
+                if !BINARY_GATES.contains(&parts[1]) {
+                    return Err(ParseError::at(
+                        line,
+                        parts[1],
+                        format!("Unknown gate type: '{}'", parts[1]),
+                    ));
+                }
                 Ok(Self {
                     ctype: Type::Gate(parts[1].to_string()),
                     left: signal_or_wire!(parts[0]),
@@ -125,7 +136,7 @@ impl Component {
 
             _ => {
                 // Any other length is invalid
-                bail!("Invalid component line: {line}");
+                Err(ParseError::new(line, 0..line.len(), "Invalid component"))
             }
         }
     }
@@ -169,36 +180,55 @@ impl Wires {
     pub fn from_input_data(input_data: &str) -> Result<Self> {
         let mut wires = Self::new();
         // Parse each line into a Component and add it to the Wires hashmap
-        for line in input_data.lines() {
-            let line = line.trim();
-            if line.is_empty() {
-                continue;
-            }
-            let parts: Vec<&str> = line.split(" -> ").map(|item| item.trim()).collect();
-            let component = Component::from_str(parts[0])?;
-            wires.0.insert(parts[1].to_string(), component);
+        for (wname, component) in parse_lines(input_data, Self::parse_connection)? {
+            wires.0.insert(wname, component);
         }
         Ok(wires)
     }
 
+    // Parses a line like "x AND y -> d" into the wire name and the component feeding it
+    fn parse_connection(line: &str) -> Result<(String, Component), ParseError> {
+        let Some((expr, wname)) = line.split_once(" -> ") else {
+            return Err(ParseError::at(
+                line,
+                line.trim(),
+                "Expected '<input> -> <wire>'",
+            ));
+        };
+        let (expr, wname) = (expr.trim(), wname.trim());
+        if wname.is_empty() || wname.contains(char::is_whitespace) {
+            return Err(ParseError::at(line, wname, "Expected a wire name"));
+        }
+        let component = Component::from_str(expr).map_err(|err| err.within(line, expr))?;
+        Ok((wname.to_string(), component))
+    }
+
     pub fn all_known(&self) -> bool {
         self.0.values().all(|c| matches!(c.ctype, Type::Signal(_)))
     }
 
+    fn known_count(&self) -> usize {
+        self.0
+            .values()
+            .filter(|c| matches!(c.ctype, Type::Signal(_)))
+            .count()
+    }
+
     pub fn compute(&mut self) -> Result<()> {
         while !self.all_known() {
+            let known = self.known_count();
             self.0.clone().iter().try_for_each(|(k, v)| {
                 match v.ctype {
                     Type::Signal(_) => Ok(()),
                     Type::Wire(ref wname) => {
-                        if let Some(wire_comp) = self.0.get(wname) {
-                            if let Type::Signal(value) = wire_comp.ctype {
-                                // Update the component to a signal
-                                let comp = self.0.get_mut(k).unwrap();
-                                comp.ctype = Type::Signal(value);
-                                comp.left = Type::None;
-                                comp.right = Type::None;
-                            }
+                        if let Some(wire_comp) = self.0.get(wname)
+                            && let Type::Signal(value) = wire_comp.ctype
+                        {
+                            // Update the component to a signal
+                            let comp = self.0.get_mut(k).unwrap();
+                            comp.ctype = Type::Signal(value);
+                            comp.left = Type::None;
+                            comp.right = Type::None;
                         }
                         Ok(())
                     }
@@ -224,8 +254,9 @@ impl Wires {
                                 let gate_val = match gate_name.as_str() {
                                     "AND" => left_val & right_val,
                                     "OR" => left_val | right_val,
-                                    "LSHIFT" => left_val << right_val,
-                                    "RSHIFT" => left_val >> right_val,
+                                    // Shifting a 16 bit signal by 16 or more clears it
+                                    "LSHIFT" => left_val.checked_shl(right_val as u32).unwrap_or(0),
+                                    "RSHIFT" => left_val.checked_shr(right_val as u32).unwrap_or(0),
                                     _ => unreachable!(),
                                 };
                                 self.0.insert(
@@ -269,6 +300,10 @@ impl Wires {
                     Type::None => Ok(()),
                 }
             })?;
+            // Wires fed by a missing wire or by a loop never get a signal
+            if self.known_count() == known {
+                bail!("{} wire(s) can never get a signal", self.0.len() - known);
+            }
         }
         Ok(())
     }
@@ -277,6 +312,7 @@ impl Wires {
 #[cfg(test)]
 mod tests {
     use super::Type;
+    use proptest::prelude::*;
     use test_case::test_case;
 
     #[test_case(Type::Signal(123_u16), Some(123_u16); "Signal")]
@@ -303,6 +339,38 @@ mod tests {
 
         let mut wires = Wires::from_input_data(test_data).unwrap();
         wires.compute().unwrap();
-        assert_eq!(wires.all_known(), true);
+        assert!(wires.all_known());
+    }
+
+    #[test]
+    fn compute_unconnected() {
+        let mut wires = super::Wires::from_input_data("x AND y -> d\n1 -> x").unwrap();
+        assert!(wires.compute().is_err());
+    }
+
+    #[test_case("x XOR y -> d", 2..5; "unknown gate")]
+    #[test_case("x y -> d", 0..1; "missing not")]
+    #[test_case("123 -> ", 7..7; "missing wire")]
+    #[test_case("  123 x", 2..7; "missing arrow")]
+    fn bad_connections(line: &str, span: std::ops::Range<usize>) {
+        let err = super::Wires::parse_connection(line).err().unwrap();
+        assert_eq!(err.text, line);
+        assert_eq!(err.span, span);
+    }
+
+    proptest! {
+        #[test]
+        fn parse_connection_never_panics(s in "(NOT )?[a-z0-9]{0,3}( AND| OR| LSHIFT| RSHIFT| XOR)? ?[a-z0-9]{0,3}( -> )?[a-z ]{0,3}|\\PC*") {
+            let _ = super::Wires::parse_connection(&s);
+        }
+
+        #[test]
+        fn compute_never_panics(
+            lines in proptest::collection::vec("(NOT )?[a-c0-9]{1,2}( AND| OR| LSHIFT| RSHIFT) [a-c0-9]{1,2} -> [a-c]", 0..6)
+        ) {
+            if let Ok(mut wires) = super::Wires::from_input_data(&lines.join("\n")) {
+                let _ = wires.compute();
+            }
+        }
     }
 }
@@ -1,9 +1,35 @@
 mod circuit;
 mod part1;
 mod part2;
+
 use anyhow::Result;
+use common::registry::{self, Registry};
+use common::{Answer, Solution};
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    fn parses(&self) -> bool {
+        true
+    }
+
+    fn parse(&self, input: &str) -> Result<()> {
+        circuit::Wires::from_input_data(input).map(|_| ())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer> {
+        part1::solve(input)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer> {
+        part2::solve(input)
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(7, Day7);
+}
+
 fn main() -> Result<()> {
-    part1::run()?;
-    part2::run()?;
-    Ok(())
+    registry::main_for(register)
 }
@@ -1,13 +1,12 @@
-use crate::circuit::Wires;
-use anyhow::Result;
+use super::circuit::Wires;
+use anyhow::{Context, Result};
+use common::Answer;
 
-pub fn run() -> Result<()> {
-    let input = common::read_input(7)?;
-    let mut wires = Wires::from_input_data(&input)?;
+pub fn solve(input: &str) -> Result<Answer> {
+    let mut wires = Wires::from_input_data(input)?;
     wires.compute()?;
-    println!(
-        "Part1: value on wire 'a': {}",
-        wires.get_wire_signal("a").unwrap()
-    );
-    Ok(())
+    let signal = wires
+        .get_wire_signal("a")
+        .context("No signal on wire 'a'")?;
+    Ok(signal.into())
 }
@@ -1,4 +1,7 @@
-use anyhow::{Result, bail};
+use anyhow::Result;
+use common::ParseError;
+use common::stream;
+use std::io::BufRead;
 
 enum State {
     Idle,
@@ -8,15 +11,17 @@ enum State {
     SkipSecondHexDigit,
 }
 
-pub fn part1_delta_chars(line: &str) -> Result<usize> {
+pub fn part1_delta_chars(line: &str) -> Result<usize, ParseError> {
     let mut delta = 2_usize; // We take into account the starting and ending double quotes, not in mem!
     let mut state = State::Idle;
 
-    for ch in line.trim().chars() {
+    let trimmed = line.trim();
+    for (i, ch) in trimmed.char_indices() {
+        let at = |message: &str| ParseError::at(line, &trimmed[i..i + ch.len_utf8()], message);
         match state {
             State::Idle => {
                 if ch != '"' {
-                    bail!("The line must start with a double quote");
+                    return Err(at("The line must start with a double quote"));
                 }
                 state = State::InString;
             }
@@ -39,31 +44,37 @@ pub fn part1_delta_chars(line: &str) -> Result<usize> {
                     state = State::SkipFirstHexDigit;
                 }
                 wtf => {
-                    bail!("Illegal escape character '{wtf}' in {line}");
+                    return Err(at(&format!("Illegal escape character '{wtf}'")));
                 }
             },
             State::SkipFirstHexDigit => {
-                assert!(ch.is_ascii_hexdigit());
+                if !ch.is_ascii_hexdigit() {
+                    return Err(at("Expected a hex digit"));
+                }
                 state = State::SkipSecondHexDigit;
             }
             State::SkipSecondHexDigit => {
-                assert!(ch.is_ascii_hexdigit());
+                if !ch.is_ascii_hexdigit() {
+                    return Err(at("Expected a hex digit"));
+                }
                 state = State::InString;
             }
         }
     }
-    unreachable!();
+    Err(ParseError::at_end(line, "Missing closing double quote"))
 }
 
-pub fn part2_delta_chars(line: &str) -> Result<usize> {
+pub fn part2_delta_chars(line: &str) -> Result<usize, ParseError> {
     let mut delta = 2_usize; // We take into account the starting and ending double quotes, not in mem!
     let mut state = State::Idle;
 
-    for ch in line.trim().chars() {
+    let trimmed = line.trim();
+    for (i, ch) in trimmed.char_indices() {
+        let at = |message: &str| ParseError::at(line, &trimmed[i..i + ch.len_utf8()], message);
         match state {
             State::Idle => {
                 if ch != '"' {
-                    bail!("The line must start with a double quote");
+                    return Err(at("The line must start with a double quote"));
                 }
                 delta += 1;
                 state = State::InString;
@@ -81,10 +92,19 @@ pub fn part2_delta_chars(line: &str) -> Result<usize> {
     Ok(delta)
 }
 
-#[cfg(test)]
+// Adds up the delta of every line as it is read, using one of the two functions above
+pub fn total_delta(
+    reader: impl BufRead,
+    delta_chars: fn(&str) -> Result<usize, ParseError>,
+) -> Result<usize> {
+    stream::parse_lines(reader, delta_chars).sum()
+}
 
+#[cfg(test)]
 mod tests {
-    use crate::delta::{part1_delta_chars, part2_delta_chars};
+    use super::{part1_delta_chars, part2_delta_chars, total_delta};
+    use proptest::prelude::*;
+    use std::ops::Range;
     use test_case::test_case;
 
     #[test_case(r#""""#, 2 ; "empty line") ]
@@ -104,4 +124,32 @@ mod tests {
         let delta = part2_delta_chars(line).unwrap();
         assert_eq!(delta, expected);
     }
+
+    #[test_case(r#"abc""#, 0..1 ; "missing opening quote") ]
+    #[test_case(r#""a\qb""#, 3..4 ; "illegal escape") ]
+    #[test_case(r#""\x2g""#, 4..5 ; "bad hex digit") ]
+    #[test_case(r#""abc"#, 4..4 ; "missing closing quote") ]
+    fn part1_delta_errors(line: &str, span: Range<usize>) {
+        let err = part1_delta_chars(line).unwrap_err();
+        assert_eq!(err.span, span);
+    }
+
+    #[test]
+    fn total_delta_from_reader() {
+        let input = "\"\"\n\"abc\"\n\n\"aaa\\\"aaa\"\n\"\\x27\"\n";
+        let reader = std::io::BufReader::with_capacity(3, input.as_bytes());
+        assert_eq!(total_delta(reader, part1_delta_chars).unwrap(), 12);
+        assert_eq!(
+            total_delta(input.as_bytes(), part2_delta_chars).unwrap(),
+            19
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn never_panics(s in r#"[\\"xa-f0-9 ]{0,12}|\PC*"#) {
+            let _ = part1_delta_chars(&s);
+            let _ = part2_delta_chars(&s);
+        }
+    }
 }
@@ -1,11 +1,13 @@
-use crate::delta::part2_delta_chars;
+use super::delta::{part2_delta_chars, total_delta};
 use anyhow::Result;
+use common::Answer;
+use std::io::BufRead;
 
-pub fn run() -> Result<()> {
-    let input = common::read_input(8)?;
-    let delta = input.lines().try_fold(0, |acc: usize, line: &str| {
-        part2_delta_chars(line).map(|v| acc + v)
-    })?;
-    println!("Difference: {}", delta);
-    Ok(())
+pub fn solve(input: &str) -> Result<Answer> {
+    solve_reader(input.as_bytes())
+}
+
+pub fn solve_reader(reader: impl BufRead) -> Result<Answer> {
+    let delta = total_delta(reader, part2_delta_chars)?;
+    Ok(delta.into())
 }
@@ -1,12 +1,8 @@
-use crate::routes::Routes;
+use super::routes::Routes;
 use anyhow::Result;
+use common::Answer;
 
-pub fn run() -> Result<()> {
-    let input = common::read_input(9)?;
-    let routes = Routes::from_input_data(&input)?;
-    println!(
-        "shortest route distance: {}",
-        routes.get_shortest_route_distance()
-    );
-    Ok(())
+pub fn solve(input: &str) -> Result<Answer> {
+    let routes = Routes::from_input_data(input)?;
+    Ok(routes.get_shortest_route_distance()?.into())
 }
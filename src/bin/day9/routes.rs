@@ -1,4 +1,6 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use common::ParseError;
+use common::parse_error::parse_lines;
 use itertools::Itertools;
 use std::fmt::Display;
 
@@ -10,12 +12,21 @@ pub struct LocationPair {
 }
 
 impl LocationPair {
-    pub fn from_string(s: &str) -> Result<Self> {
+    pub fn from_string(s: &str) -> Result<Self, ParseError> {
         let parts: Vec<&str> = s.split_ascii_whitespace().collect();
-        let d = parts[4].parse::<usize>()?;
+        let [a, "to", b, "=", d] = parts.as_slice() else {
+            return Err(ParseError::at(
+                s,
+                s.trim(),
+                "Expected '<location> to <location> = <distance>'",
+            ));
+        };
+        let d = d
+            .parse::<usize>()
+            .map_err(|_| ParseError::at(s, d, format!("Bad distance '{d}'")))?;
         Ok(Self {
-            a: String::from(parts[0]),
-            b: String::from(parts[2]),
+            a: String::from(*a),
+            b: String::from(*b),
             d,
         })
     }
@@ -82,7 +93,10 @@ impl Display for Routes {
 
         writeln!(f, "  routes: {{")?;
         for (i, route) in self.routes.iter().enumerate() {
-            writeln!(f, "    {}: {}", *route, self.calc_route_distance(i))?;
+            match self.calc_route_distance(i) {
+                Ok(distance) => writeln!(f, "    {}: {distance}", *route)?,
+                Err(_) => writeln!(f, "    {}: out of range", *route)?,
+            }
         }
         writeln!(f, "  }}")?;
 
@@ -102,13 +116,9 @@ impl Routes {
     pub fn from_input_data(input_data: &str) -> Result<Self> {
         let mut routes = Routes::new();
         let mut start_route = Route::new();
-        for line in input_data.lines() {
-            let lpline = line.trim();
-            if !lpline.is_empty() {
-                let locp = LocationPair::from_string(lpline)?;
-                start_route.add_location_pair(&locp);
-                routes.loc_pairs.push(locp);
-            }
+        for locp in parse_lines(input_data, LocationPair::from_string)? {
+            start_route.add_location_pair(&locp);
+            routes.loc_pairs.push(locp);
         }
 
         let perms = start_route.0.iter().permutations(start_route.0.len());
@@ -127,32 +137,27 @@ impl Routes {
         0
     }
 
-    pub fn calc_route_distance(&self, i: usize) -> usize {
+    pub fn calc_route_distance(&self, i: usize) -> Result<usize> {
         let route = self.routes.get(i).unwrap();
-        let route_slice = route.0.as_slice();
-        let mut tot_dist = 0_usize;
-        for i in 0..route_slice.len() - 1 {
-            tot_dist += self.distance_between_locations(&route_slice[i], &route_slice[i + 1]);
-        }
-        tot_dist
+        route.0.windows(2).try_fold(0_usize, |total, pair| {
+            total
+                .checked_add(self.distance_between_locations(&pair[0], &pair[1]))
+                .with_context(|| format!("Distance out of range for {route}"))
+        })
     }
 
-    pub fn get_shortest_route_distance(&self) -> usize {
-        let mut shortest_distance = 1_000_000_usize;
-        for i in 0..self.routes.len() {
-            let dist = self.calc_route_distance(i);
-            shortest_distance = std::cmp::min(shortest_distance, dist);
-        }
-        shortest_distance
+    pub fn get_shortest_route_distance(&self) -> Result<usize> {
+        let distances: Vec<usize> = (0..self.routes.len())
+            .map(|i| self.calc_route_distance(i))
+            .collect::<Result<_>>()?;
+        Ok(distances.into_iter().min().unwrap_or(0))
     }
 
-    pub fn get_longest_route_distance(&self) -> usize {
-        let mut longest_distance = 0_usize;
-        for i in 0..self.routes.len() {
-            let dist = self.calc_route_distance(i);
-            longest_distance = std::cmp::max(longest_distance, dist);
-        }
-        longest_distance
+    pub fn get_longest_route_distance(&self) -> Result<usize> {
+        let distances: Vec<usize> = (0..self.routes.len())
+            .map(|i| self.calc_route_distance(i))
+            .collect::<Result<_>>()?;
+        Ok(distances.into_iter().max().unwrap_or(0))
     }
 }
 
@@ -161,7 +166,8 @@ impl Routes {
 #[cfg(test)]
 mod tests {
 
-    use crate::routes::{LocationPair, Route, Routes};
+    use super::{LocationPair, Route, Routes};
+    use proptest::prelude::*;
 
     #[test]
     fn distance_from_string() {
@@ -170,6 +176,14 @@ mod tests {
         assert_eq!(location.b, String::from("Dublin"));
         assert_eq!(location.d, 464);
     }
+
+    #[test]
+    fn distance_from_bad_string() {
+        let err = LocationPair::from_string("London to Dublin = far").unwrap_err();
+        assert_eq!(err.span, 19..22);
+        let err = LocationPair::from_string("London to Dublin").unwrap_err();
+        assert_eq!(err.span, 0..16);
+    }
     // #endregion
 
     #[test]
@@ -202,7 +216,7 @@ mod tests {
     "#;
 
         let routes = Routes::from_input_data(input_data).unwrap();
-        let shortest = routes.get_shortest_route_distance();
+        let shortest = routes.get_shortest_route_distance().unwrap();
         assert_eq!(shortest, 605);
     }
 
@@ -215,7 +229,26 @@ mod tests {
     "#;
 
         let routes = Routes::from_input_data(input_data).unwrap();
-        let longest = routes.get_longest_route_distance();
+        let longest = routes.get_longest_route_distance().unwrap();
         assert_eq!(longest, 982);
     }
+
+    #[test]
+    fn route_distance_overflow() {
+        let routes = Routes::from_input_data("A to B = 18446744073709551615\nB to C = 5").unwrap();
+        assert!(routes.get_shortest_route_distance().is_err());
+        assert!(routes.get_longest_route_distance().is_err());
+        // Above the old starting guess for the shortest distance
+        let routes =
+            Routes::from_input_data("A to B = 2000000\nB to C = 3000000\nA to C = 2500000")
+                .unwrap();
+        assert_eq!(routes.get_shortest_route_distance().unwrap(), 4_500_000);
+    }
+
+    proptest! {
+        #[test]
+        fn from_string_never_panics(s in "[A-Za-z]{0,6}( to)? ?[A-Za-z]{0,6}( =)? ?[0-9a-z-]{0,22}|\\PC*") {
+            let _ = LocationPair::from_string(&s);
+        }
+    }
 }
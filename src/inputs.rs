@@ -0,0 +1,210 @@
+use crate::INPUT_DIR_VAR;
+use anyhow::{Context, Result, bail};
+use std::path::{Path, PathBuf};
+
+pub const SESSION_VAR: &str = "AOC_SESSION";
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+const YEAR: u16 = 2015;
+const USER_AGENT: &str = "github.com/flevin58/advent_of_code_2015";
+
+// Downloads puzzle inputs once and keeps them as dayN/input.txt under `dir`,
+// the same layout `InputSource` reads from.
+#[derive(Clone, Debug)]
+pub struct InputCache {
+    dir: PathBuf,
+    base_url: String,
+    session: Option<String>,
+}
+
+impl InputCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            session: None,
+        }
+    }
+
+    // Caches into $AOC_INPUT_DIR when set, otherwise next to the sources in src/bin
+    pub fn from_env() -> Self {
+        let dir = std::env::var_os(INPUT_DIR_VAR)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("src/bin"));
+        let mut cache = Self::new(dir);
+        if let Ok(base_url) = std::env::var(BASE_URL_VAR) {
+            cache = cache.with_base_url(&base_url);
+        }
+        if let Ok(session) = std::env::var(SESSION_VAR) {
+            cache = cache.with_session(&session);
+        }
+        cache
+    }
+
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn with_session(mut self, session: &str) -> Self {
+        self.session = Some(session.trim().to_string());
+        self
+    }
+
+    pub fn path(&self, day: u8) -> PathBuf {
+        self.dir.join(format!("day{day}")).join("input.txt")
+    }
+
+    pub fn is_cached(&self, day: u8) -> bool {
+        self.path(day).is_file()
+    }
+
+    pub fn url(&self, day: u8) -> String {
+        format!("{}/{YEAR}/day/{day}/input", self.base_url)
+    }
+
+    // Only goes to the network when the input is not cached yet
+    pub fn get(&self, day: u8) -> Result<String> {
+        let path = self.path(day);
+        if self.is_cached(day) {
+            return std::fs::read_to_string(&path)
+                .with_context(|| format!("Cannot read cached input {}", path.display()));
+        }
+        let input = self.fetch(day)?;
+        self.store(&path, &input)?;
+        Ok(input)
+    }
+
+    fn fetch(&self, day: u8) -> Result<String> {
+        let Some(session) = &self.session else {
+            bail!("Input for day {day} is not cached and {SESSION_VAR} is not set");
+        };
+        let url = self.url(day);
+        let input = ureq::get(&url)
+            .header("Cookie", &format!("session={session}"))
+            .header("User-Agent", USER_AGENT)
+            .call()
+            .and_then(|mut response| response.body_mut().read_to_string())
+            .with_context(|| format!("Cannot fetch {url}"))?;
+        if input.is_empty() {
+            bail!("Empty input received from {url}");
+        }
+        Ok(input)
+    }
+
+    // Write to a temporary file first so that a failed write never looks like a cached input
+    fn store(&self, path: &Path, input: &str) -> Result<()> {
+        let dir = path.parent().unwrap();
+        std::fs::create_dir_all(dir).with_context(|| format!("Cannot create {}", dir.display()))?;
+        let tmp = path.with_extension("txt.part");
+        std::fs::write(&tmp, input).with_context(|| format!("Cannot write {}", tmp.display()))?;
+        std::fs::rename(&tmp, path).with_context(|| format!("Cannot write {}", path.display()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    // Minimal stand-in for the puzzle server: answers every request with `status` and `body`
+    // and counts the requests that carried the expected session cookie on the expected path.
+    fn mock_server(status: u16, body: &'static str) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request = Vec::new();
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    request.push(line.trim().to_lowercase());
+                }
+                if request[0].starts_with("get /2015/day/3/input ")
+                    && request.contains(&"cookie: session=secret".to_string())
+                {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                }
+                let response = format!(
+                    "HTTP/1.1 {status} Mock\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        (base_url, hits)
+    }
+
+    fn temp_cache(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc_inputs_{name}_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn fetches_once_then_uses_cache() {
+        let (base_url, hits) = mock_server(200, "^>v<");
+        let dir = temp_cache("fetch_once");
+        let cache = InputCache::new(&dir)
+            .with_base_url(&base_url)
+            .with_session("secret");
+
+        assert!(!cache.is_cached(3));
+        assert_eq!(cache.get(3).unwrap(), "^>v<");
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+        assert!(cache.is_cached(3));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("day3/input.txt")).unwrap(),
+            "^>v<"
+        );
+
+        assert_eq!(cache.get(3).unwrap(), "^>v<");
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn cached_input_needs_no_network() {
+        let dir = temp_cache("offline");
+        std::fs::create_dir_all(dir.join("day5")).unwrap();
+        std::fs::write(dir.join("day5/input.txt"), "aaa").unwrap();
+        // Nothing listens on this address, any request would fail
+        let cache = InputCache::new(&dir).with_base_url("http://127.0.0.1:1");
+        assert_eq!(cache.get(5).unwrap(), "aaa");
+    }
+
+    #[test]
+    fn missing_session_is_an_error() {
+        let cache = InputCache::new(temp_cache("no_session"));
+        let err = cache.get(1).unwrap_err().to_string();
+        assert!(err.contains(SESSION_VAR));
+    }
+
+    #[test]
+    fn http_errors_are_not_cached() {
+        let (base_url, _) = mock_server(404, "Not found");
+        let dir = temp_cache("http_error");
+        let cache = InputCache::new(&dir)
+            .with_base_url(&base_url)
+            .with_session("secret");
+        assert!(cache.get(3).is_err());
+        assert!(!cache.is_cached(3));
+    }
+
+    #[test]
+    fn url() {
+        let cache = InputCache::new("inputs").with_base_url("http://localhost:8080/");
+        assert_eq!(cache.url(7), "http://localhost:8080/2015/day/7/input");
+    }
+}
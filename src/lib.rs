@@ -1,7 +1,265 @@
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
 
-pub fn read_input(day: u8) -> Result<String> {
-    let path = format!("src/bin/day{day}/input.txt");
-    let input = std::fs::read_to_string(path)?;
+pub mod alphabet;
+pub mod answers;
+pub mod bench;
+pub mod inputs;
+pub mod parse_error;
+pub mod registry;
+pub mod scaffold;
+pub mod stream;
+
+pub use parse_error::ParseError;
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum Answer {
+    Integer(i64),
+    String(String),
+    None,
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Integer(value) => write!(f, "{value}"),
+            Answer::String(value) => write!(f, "{value}"),
+            Answer::None => write!(f, "None"),
+        }
+    }
+}
+
+// Integers too large for an i64 keep their exact digits as a string rather than wrapping
+macro_rules! answer_from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    match i64::try_from(value) {
+                        Ok(value) => Answer::Integer(value),
+                        Err(_) => Answer::String(value.to_string()),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+answer_from_integer!(u16, i32, u32, i64, u64, usize);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::String(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::String(value.to_string())
+    }
+}
+
+// Every day implements this to expose both parts of its puzzle to the runner
+pub trait Solution {
+    // Only used to time parsing on its own. Days with a separate parsing step override `parse`
+    // and return true from `parses`, the others keep both defaults and get no parse timing.
+    fn parses(&self) -> bool {
+        false
+    }
+    fn parse(&self, _input: &str) -> Result<()> {
+        Ok(())
+    }
+    fn part1(&self, input: &str) -> Result<Answer>;
+    fn part2(&self, input: &str) -> Result<Answer>;
+
+    // Days that can answer while reading their input return true and override the two
+    // `_from_reader` parts, the registry then never holds their whole input in memory
+    fn streams(&self) -> bool {
+        false
+    }
+    fn part1_from_reader(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        self.part1(&read_all(reader)?)
+    }
+    fn part2_from_reader(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        self.part2(&read_all(reader)?)
+    }
+}
+
+fn read_all(reader: &mut dyn BufRead) -> Result<String> {
+    let mut input = String::new();
+    reader
+        .read_to_string(&mut input)
+        .context("Cannot read input")?;
     Ok(input)
 }
+
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+const STDIN: &str = "-";
+
+// Where to look for a day's puzzle input, see `InputSource::candidates` for the order
+#[derive(Clone, Debug, Default)]
+pub struct InputSource {
+    explicit: Option<PathBuf>,
+    input_dir: Option<PathBuf>,
+}
+
+impl InputSource {
+    pub fn new(explicit: Option<PathBuf>) -> Self {
+        Self {
+            explicit,
+            input_dir: std::env::var_os(INPUT_DIR_VAR).map(PathBuf::from),
+        }
+    }
+
+    // An explicit path (or "-" for stdin) is the only candidate when given, otherwise
+    // $AOC_INPUT_DIR/dayN/input.txt, then src/bin/dayN/input.txt relative to the
+    // current directory and finally relative to the crate root.
+    fn candidates(&self, day: u8) -> Vec<PathBuf> {
+        if let Some(path) = &self.explicit {
+            return vec![path.clone()];
+        }
+        let day_path = format!("day{day}/input.txt");
+        let mut candidates = Vec::new();
+        if let Some(dir) = &self.input_dir {
+            candidates.push(dir.join(&day_path));
+        }
+        candidates.push(Path::new("src/bin").join(&day_path));
+        candidates.push(
+            Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("src/bin")
+                .join(&day_path),
+        );
+        candidates
+    }
+
+    // Stdin can only be read once, whatever the day
+    pub fn reads_stdin(&self) -> bool {
+        self.explicit
+            .as_ref()
+            .is_some_and(|path| path.as_os_str() == STDIN)
+    }
+
+    pub fn read(&self, day: u8) -> Result<String> {
+        self.read_with_path(day).map(|(input, _)| input)
+    }
+
+    // Also returns the file the input was read from, None when it came from stdin
+    pub fn read_with_path(&self, day: u8) -> Result<(String, Option<PathBuf>)> {
+        let mut tried = Vec::new();
+        for path in self.candidates(day) {
+            if path.as_os_str() == STDIN {
+                let mut input = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut input)
+                    .context("Cannot read input from stdin")?;
+                return Ok((input, None));
+            }
+            match std::fs::read_to_string(&path) {
+                Ok(input) => return Ok((input, Some(path))),
+                Err(err) => tried.push(format!("  {}: {err}", path.display())),
+            }
+        }
+        bail!("No input found for day {day}, tried:\n{}", tried.join("\n"));
+    }
+
+    // Same lookup as `read_with_path`, but leaves reading to the caller, see `stream`
+    pub fn open(&self, day: u8) -> Result<Box<dyn BufRead>> {
+        self.open_with_path(day).map(|(reader, _)| reader)
+    }
+
+    pub fn open_with_path(&self, day: u8) -> Result<(Box<dyn BufRead>, Option<PathBuf>)> {
+        let mut tried = Vec::new();
+        for path in self.candidates(day) {
+            if path.as_os_str() == STDIN {
+                return Ok((Box::new(std::io::stdin().lock()), None));
+            }
+            match File::open(&path) {
+                Ok(file) => return Ok((Box::new(BufReader::new(file)), Some(path))),
+                Err(err) => tried.push(format!("  {}: {err}", path.display())),
+            }
+        }
+        bail!("No input found for day {day}, tried:\n{}", tried.join("\n"));
+    }
+}
+
+pub fn read_input(day: u8) -> Result<String> {
+    InputSource::new(None).read(day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Answer, InputSource};
+    use std::io::Read;
+    use std::path::PathBuf;
+    use test_case::test_case;
+
+    #[test_case(Answer::from(74), "74"; "integer")]
+    #[test_case(Answer::from(usize::MAX >> 1), "9223372036854775807"; "usize")]
+    #[test_case(Answer::from(u64::MAX), "18446744073709551615"; "u64 above i64")]
+    #[test_case(Answer::from(-5), "-5"; "negative")]
+    #[test_case(Answer::from("cqjxxyzz"), "cqjxxyzz"; "string")]
+    #[test_case(Answer::None, "None"; "none")]
+    fn display(answer: Answer, expected: &str) {
+        assert_eq!(answer.to_string(), expected);
+    }
+
+    #[test]
+    fn large_integers_do_not_wrap() {
+        assert_eq!(Answer::from(i64::MAX as u64), Answer::Integer(i64::MAX));
+        assert_eq!(
+            Answer::from(i64::MAX as u64 + 1),
+            Answer::from("9223372036854775808")
+        );
+    }
+
+    #[test]
+    fn input_candidates() {
+        let source = InputSource {
+            explicit: None,
+            input_dir: Some(PathBuf::from("/inputs")),
+        };
+        let candidates = source.candidates(3);
+        assert_eq!(candidates[0], PathBuf::from("/inputs/day3/input.txt"));
+        assert_eq!(candidates[1], PathBuf::from("src/bin/day3/input.txt"));
+        assert!(candidates[2].ends_with("src/bin/day3/input.txt"));
+    }
+
+    #[test]
+    fn explicit_input_wins() {
+        let path = std::env::temp_dir().join("aoc_explicit_input_wins.txt");
+        std::fs::write(&path, "(()").unwrap();
+        let source = InputSource {
+            explicit: Some(path.clone()),
+            input_dir: Some(PathBuf::from("/inputs")),
+        };
+        assert_eq!(source.candidates(1), vec![path.clone()]);
+        assert_eq!(source.read(1).unwrap(), "(()");
+        let mut streamed = String::new();
+        source
+            .open(1)
+            .unwrap()
+            .read_to_string(&mut streamed)
+            .unwrap();
+        assert_eq!(streamed, "(()");
+        assert_eq!(source.open_with_path(1).unwrap().1, Some(path));
+        assert!(!source.reads_stdin());
+        assert!(InputSource::new(Some(PathBuf::from("-"))).reads_stdin());
+    }
+
+    #[test]
+    fn missing_input_lists_every_location() {
+        let source = InputSource {
+            explicit: None,
+            input_dir: Some(PathBuf::from("/nowhere")),
+        };
+        let err = source.read(99).unwrap_err().to_string();
+        assert!(err.starts_with("No input found for day 99, tried:"));
+        assert!(err.contains("/nowhere/day99/input.txt"));
+        assert_eq!(err.matches("day99/input.txt").count(), 3);
+    }
+}
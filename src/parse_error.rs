@@ -0,0 +1,298 @@
+use std::fmt::Display;
+use std::ops::Range;
+
+// Longest part of a line shown when rendering, longer lines are clipped around the span
+const MAX_SHOWN: usize = 60;
+
+// A puzzle input that could not be parsed, with enough context to point at the problem.
+// `span` is a byte range within `text`, the offending input line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: Option<u8>,
+    pub line: Option<usize>,
+    pub span: Range<usize>,
+    pub text: String,
+    pub message: String,
+    // Characters of the line left out before `text`, when only its end could be kept
+    pub column_offset: usize,
+}
+
+impl ParseError {
+    pub fn new(text: &str, span: Range<usize>, message: impl Into<String>) -> Self {
+        let end = span.end.min(text.len());
+        Self {
+            day: None,
+            line: None,
+            span: span.start.min(end)..end,
+            text: text.to_string(),
+            message: message.into(),
+            column_offset: 0,
+        }
+    }
+
+    // Points at `part`, which must be a slice of `text` (e.g. one of its split tokens)
+    pub fn at(text: &str, part: &str, message: impl Into<String>) -> Self {
+        let start = (part.as_ptr() as usize).wrapping_sub(text.as_ptr() as usize);
+        if start <= text.len() && start + part.len() <= text.len() {
+            Self::new(text, start..start + part.len(), message)
+        } else {
+            Self::new(text, 0..text.len(), message)
+        }
+    }
+
+    // Points just past the end of `text`, for something that is missing
+    pub fn at_end(text: &str, message: impl Into<String>) -> Self {
+        Self::new(text, text.len()..text.len(), message)
+    }
+
+    // Locates a byte range of a multi-line input, filling in the line number. A span past the
+    // end is clipped, and one that splits a character is widened to the whole character.
+    pub fn in_input(input: &str, span: Range<usize>, message: impl Into<String>) -> Self {
+        let end = input.ceil_char_boundary(span.end.min(input.len()));
+        let span = input.floor_char_boundary(span.start.min(end))..end;
+        let line_start = input[..span.start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[line_start..]
+            .find('\n')
+            .map_or(input.len(), |i| line_start + i);
+        let line = input[..line_start].matches('\n').count() + 1;
+        Self::new(
+            &input[line_start..line_end],
+            span.start - line_start..span.end - line_start,
+            message,
+        )
+        .with_line(line)
+    }
+
+    // Same as `in_input` for 1-based line and column numbers, as reported by serde_json
+    pub fn at_line_column(
+        input: &str,
+        line: usize,
+        column: usize,
+        message: impl Into<String>,
+    ) -> Self {
+        let text = input.lines().nth(line.saturating_sub(1)).unwrap_or("");
+        let start = text
+            .char_indices()
+            .nth(column.saturating_sub(1))
+            .map_or(text.len(), |(i, _)| i);
+        let end = text[start..]
+            .chars()
+            .next()
+            .map_or(start, |ch| start + ch.len_utf8());
+        Self::new(text, start..end, message).with_line(line)
+    }
+
+    // Moves an error raised on `part`, a slice of `text`, so that it points into the whole `text`.
+    // The error is left as is when `part` is not a slice of `text`.
+    pub fn within(self, text: &str, part: &str) -> Self {
+        let offset = (part.as_ptr() as usize).wrapping_sub(text.as_ptr() as usize);
+        let inside = offset
+            .checked_add(part.len())
+            .is_some_and(|end| end <= text.len());
+        if !inside || self.text != part {
+            return self;
+        }
+        Self {
+            span: self.span.start + offset..self.span.end + offset,
+            text: text.to_string(),
+            ..self
+        }
+    }
+
+    pub fn with_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    pub fn with_day(mut self, day: u8) -> Self {
+        self.day = Some(day);
+        self
+    }
+
+    // 1-based column of the start of the span, counted in characters
+    pub fn column(&self) -> usize {
+        self.column_offset + self.text[..self.span.start].chars().count() + 1
+    }
+
+    pub fn render(&self) -> String {
+        let mut location = Vec::new();
+        if let Some(day) = self.day {
+            location.push(format!("day {day}"));
+        }
+        if let Some(line) = self.line {
+            location.push(format!("line {line}"));
+        }
+        location.push(format!("column {}", self.column()));
+
+        let chars: Vec<char> = self.text.chars().collect();
+        let start = self.text[..self.span.start].chars().count();
+        let len = self.text[self.span.clone()].chars().count().max(1);
+        // Keep the span visible when the line is too long to show whole
+        let first = if chars.len() > MAX_SHOWN {
+            start
+                .saturating_sub(MAX_SHOWN / 2)
+                .min(chars.len() - MAX_SHOWN)
+        } else {
+            0
+        };
+        let last = (first + MAX_SHOWN).min(chars.len());
+        let prefix = if first > 0 || self.column_offset > 0 {
+            "..."
+        } else {
+            ""
+        };
+        let suffix = if last < chars.len() { "..." } else { "" };
+        let shown: String = chars[first..last].iter().collect();
+        let caret_pad = prefix.len() + start - first;
+        let caret_len = len.min(last.saturating_sub(start)).max(1);
+
+        let number = self.line.map(|l| l.to_string()).unwrap_or_default();
+        let gutter = " ".repeat(number.len());
+        format!(
+            "{}\n{gutter}--> {}\n{gutter} |\n{number} | {prefix}{shown}{suffix}\n{gutter} | {}{}",
+            self.message,
+            location.join(", "),
+            " ".repeat(caret_pad),
+            "^".repeat(caret_len)
+        )
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// Tags a ParseError carried by `err` with the day whose input it came from
+pub fn with_day(mut err: anyhow::Error, day: u8) -> anyhow::Error {
+    if let Some(parse_error) = err.downcast_mut::<ParseError>() {
+        parse_error.day.get_or_insert(day);
+    }
+    err
+}
+
+// Parses every non-blank line of `input`, a failure is tagged with its 1-based line number
+pub fn parse_lines<'a, T>(
+    input: &'a str,
+    mut parse: impl FnMut(&'a str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| parse(line).map_err(|err| err.with_line(i + 1)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test]
+    fn at_token() {
+        let text = "London to Dublin = x464";
+        let token = text.split_whitespace().nth(4).unwrap();
+        let err = ParseError::at(text, token, "Expected a distance");
+        assert_eq!(err.span, 19..23);
+        assert_eq!(err.column(), 20);
+    }
+
+    #[test]
+    fn at_foreign_slice() {
+        let err = ParseError::at("abc", "other", "Bad");
+        assert_eq!(err.span, 0..3);
+    }
+
+    #[test]
+    fn in_input() {
+        let input = "(()\n)x(\n((";
+        let err = ParseError::in_input(input, 5..6, "Bad character");
+        assert_eq!(err.line, Some(2));
+        assert_eq!(err.text, ")x(");
+        assert_eq!(err.span, 1..2);
+    }
+
+    #[test_case("(()\n)x(\n((", 5..60, ")x(", 1..3; "past the end of the line")]
+    #[test_case("(()\n)\né", 6..99, "é", 0..2; "past the end of the input")]
+    #[test_case("(()\n)\né", 7..8, "é", 0..2; "inside a character")]
+    fn in_input_out_of_range(input: &str, span: Range<usize>, text: &str, expected: Range<usize>) {
+        let err = ParseError::in_input(input, span, "Bad character");
+        assert_eq!(err.text, text);
+        assert_eq!(err.span, expected);
+        let _ = err.render();
+    }
+
+    #[test]
+    fn at_line_column() {
+        let err = ParseError::at_line_column("[1,\n 2,}", 2, 4, "Expected value");
+        assert_eq!(err.line, Some(2));
+        assert_eq!(err.text, " 2,}");
+        assert_eq!(err.span, 3..4);
+    }
+
+    #[test]
+    fn within() {
+        let line = "123 -> x";
+        let expr = &line[..3];
+        let err = ParseError::at(expr, &expr[1..2], "Bad digit").within(line, expr);
+        assert_eq!(err.text, line);
+        assert_eq!(err.span, 1..2);
+        let err = ParseError::at_end(&line[4..], "Missing wire").within(line, &line[4..]);
+        assert_eq!(err.span, 8..8);
+    }
+
+    #[test]
+    fn within_foreign_text() {
+        let input = "abc -> x";
+        let (part, text) = (&input[..3], &input[4..]);
+        let err = ParseError::at(part, &part[1..2], "Bad digit");
+        assert_eq!(err.clone().within(text, part), err);
+    }
+
+    #[test]
+    fn parse_lines_numbers_failures() {
+        let parse = |line: &str| {
+            line.trim()
+                .parse::<u32>()
+                .map_err(|_| ParseError::at(line, line.trim(), "Expected a number"))
+        };
+        assert_eq!(parse_lines("1\n\n 2\n", parse).unwrap(), vec![1, 2]);
+        let err = parse_lines("1\n\n x\n", parse).unwrap_err();
+        assert_eq!(err.line, Some(3));
+        assert_eq!(err.column(), 2);
+    }
+
+    #[test]
+    fn render() {
+        let err = ParseError::new("2x?x4", 2..3, "Expected a number")
+            .with_line(3)
+            .with_day(2);
+        assert_eq!(
+            err.render(),
+            "Expected a number\n --> day 2, line 3, column 3\n  |\n3 | 2x?x4\n  |   ^"
+        );
+    }
+
+    #[test]
+    fn render_clips_long_lines() {
+        let text = format!("{}x{}", "(".repeat(100), ")".repeat(100));
+        let err = ParseError::new(&text, 100..101, "Bad character");
+        let rendered = err.render();
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert!(lines[3].starts_with(" | ...((("));
+        assert!(lines[3].ends_with(")))..."));
+        let caret = lines[4].find('^').unwrap();
+        assert_eq!(&lines[3][caret..caret + 1], "x");
+    }
+
+    #[test]
+    fn with_day_on_anyhow() {
+        let err = anyhow::Error::new(ParseError::at_end("1x2", "Missing height"));
+        let err = with_day(err, 2);
+        assert_eq!(err.downcast_ref::<ParseError>().unwrap().day, Some(2));
+    }
+}
@@ -0,0 +1,284 @@
+use crate::answers::{self, ExpectedAnswers, Outcome};
+use crate::{Answer, InputSource, Solution, parse_error};
+use anyhow::{Result, bail};
+use clap::{Parser, Subcommand};
+use std::collections::BTreeMap;
+use std::io::BufRead;
+use std::path::PathBuf;
+
+const PARTS: [u8; 2] = [1, 2];
+
+// Keeps every registered solution, keyed and ordered by day
+#[derive(Default)]
+pub struct Registry {
+    solutions: BTreeMap<u8, Box<dyn Solution>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, day: u8, solution: impl Solution + 'static) {
+        self.solutions.insert(day, Box::new(solution));
+    }
+
+    pub fn days(&self) -> Vec<u8> {
+        self.solutions.keys().copied().collect()
+    }
+
+    pub fn parts(&self, day: u8) -> Vec<u8> {
+        if self.solutions.contains_key(&day) {
+            PARTS.to_vec()
+        } else {
+            Vec::new()
+        }
+    }
+
+    pub fn solution(&self, day: u8) -> Result<&dyn Solution> {
+        match self.solutions.get(&day) {
+            Some(solution) => Ok(solution.as_ref()),
+            None => bail!("No solution registered for day {day}"),
+        }
+    }
+
+    pub fn solve(&self, day: u8, part: u8, input: &str) -> Result<Answer> {
+        let solution = self.solution(day)?;
+        let answer = match part {
+            1 => solution.part1(input),
+            2 => solution.part2(input),
+            _ => bail!("No part {part} for day {day}"),
+        };
+        answer.map_err(|err| parse_error::with_day(err, day))
+    }
+
+    // Same as `solve`, for days that stream their input
+    pub fn solve_reader(&self, day: u8, part: u8, reader: &mut dyn BufRead) -> Result<Answer> {
+        let solution = self.solution(day)?;
+        let answer = match part {
+            1 => solution.part1_from_reader(reader),
+            2 => solution.part2_from_reader(reader),
+            _ => bail!("No part {part} for day {day}"),
+        };
+        answer.map_err(|err| parse_error::with_day(err, day))
+    }
+
+    pub fn run(&self, day: u8, part: u8, source: &InputSource) -> Result<Outcome> {
+        let (answer, path) = if self.solution(day)?.streams() {
+            let (mut reader, path) = source.open_with_path(day)?;
+            (self.solve_reader(day, part, &mut reader)?, path)
+        } else {
+            let (input, path) = source.read_with_path(day)?;
+            (self.solve(day, part, &input)?, path)
+        };
+        let expected = ExpectedAnswers::for_input(path.as_deref())?;
+        Ok(outcome(day, part, answer, &expected))
+    }
+
+    pub fn run_day(&self, day: u8, source: &InputSource) -> Result<Vec<Outcome>> {
+        let parts = self.parts(day);
+        if parts.is_empty() {
+            bail!("No solution registered for day {day}");
+        }
+        // A streaming day opens its input again for every part, unless it can only be read once
+        if self.solution(day)?.streams() && !source.reads_stdin() {
+            return parts
+                .into_iter()
+                .map(|part| self.run(day, part, source))
+                .collect();
+        }
+        // Read once so that both parts can share a single stdin input
+        let (input, path) = source.read_with_path(day)?;
+        let expected = ExpectedAnswers::for_input(path.as_deref())?;
+        parts
+            .into_iter()
+            .map(|part| {
+                Ok(outcome(
+                    day,
+                    part,
+                    self.solve(day, part, &input)?,
+                    &expected,
+                ))
+            })
+            .collect()
+    }
+
+    pub fn run_all(&self, source: &InputSource) -> Result<Vec<Outcome>> {
+        let mut outcomes = Vec::new();
+        for day in self.days() {
+            outcomes.extend(self.run_day(day, source)?);
+        }
+        Ok(outcomes)
+    }
+}
+
+fn outcome(day: u8, part: u8, answer: Answer, expected: &ExpectedAnswers) -> Outcome {
+    let verdict = expected.verify(part, &answer);
+    Outcome {
+        day,
+        part,
+        answer,
+        verdict,
+    }
+}
+
+// The whole `main` of a day's own binary. An optional first argument overrides the input
+// file, "-" reads stdin.
+pub fn main_for(register: fn(&mut Registry)) -> Result<()> {
+    main_with_input(register, std::env::args_os().nth(1).map(PathBuf::from))
+}
+
+// Command line of a day binary that has subcommands `C` of its own
+#[derive(Parser)]
+#[command(args_conflicts_with_subcommands = true)]
+struct DayCli<C: Subcommand> {
+    #[command(subcommand)]
+    command: Option<C>,
+    /// Input file to use instead of the day's input.txt, "-" reads stdin
+    input: Option<PathBuf>,
+}
+
+// Same as `main_for`, handing any of the day's own subcommands to `run`
+pub fn main_with<C: Subcommand>(
+    register: fn(&mut Registry),
+    run: fn(C) -> Result<()>,
+) -> Result<()> {
+    let cli = DayCli::<C>::parse();
+    match cli.command {
+        Some(command) => run(command),
+        None => main_with_input(register, cli.input),
+    }
+}
+
+fn main_with_input(register: fn(&mut Registry), input: Option<PathBuf>) -> Result<()> {
+    let mut registry = Registry::new();
+    register(&mut registry);
+    let outcomes = registry.run_all(&InputSource::new(input))?;
+    outcomes.iter().for_each(|outcome| println!("{outcome}"));
+    answers::ensure_no_failures(&outcomes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answers::Verdict;
+
+    struct Length;
+
+    impl Solution for Length {
+        fn part1(&self, input: &str) -> Result<Answer> {
+            Ok(input.len().into())
+        }
+
+        fn part2(&self, input: &str) -> Result<Answer> {
+            Ok(input.to_uppercase().into())
+        }
+    }
+
+    // Only ever answers from a stream
+    struct Lines;
+
+    impl Solution for Lines {
+        fn part1(&self, _input: &str) -> Result<Answer> {
+            bail!("Expected to stream")
+        }
+
+        fn part2(&self, _input: &str) -> Result<Answer> {
+            bail!("Expected to stream")
+        }
+
+        fn streams(&self) -> bool {
+            true
+        }
+
+        fn part1_from_reader(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+            Ok(reader.lines().count().into())
+        }
+
+        fn part2_from_reader(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+            let mut input = String::new();
+            reader.read_to_string(&mut input)?;
+            Ok(input.len().into())
+        }
+    }
+
+    fn registry() -> Registry {
+        let mut registry = Registry::new();
+        registry.register(7, Length);
+        registry.register(1, Length);
+        registry
+    }
+
+    #[test]
+    fn days_and_parts_are_sorted() {
+        let registry = registry();
+        assert_eq!(registry.days(), vec![1, 7]);
+        assert_eq!(registry.parts(7), vec![1, 2]);
+        assert!(registry.parts(3).is_empty());
+    }
+
+    #[test]
+    fn solve() {
+        let registry = registry();
+        assert_eq!(registry.solve(1, 1, "abc").unwrap(), Answer::Integer(3));
+        assert_eq!(registry.solve(7, 2, "abc").unwrap(), Answer::from("ABC"));
+    }
+
+    #[test]
+    fn solve_reader() {
+        let mut registry = registry();
+        registry.register(4, Lines);
+        let solve = |day, part| registry.solve_reader(day, part, &mut "ab\nc\n".as_bytes());
+        assert_eq!(solve(4, 1).unwrap(), Answer::Integer(2));
+        assert_eq!(solve(4, 2).unwrap(), Answer::Integer(5));
+        assert_eq!(solve(1, 1).unwrap(), Answer::Integer(5));
+        assert!(solve(4, 3).is_err());
+    }
+
+    #[test]
+    fn run_streaming_day() {
+        let path = std::env::temp_dir().join("aoc_registry_streaming_day.txt");
+        std::fs::write(&path, "ab\nc\n").unwrap();
+        let mut registry = Registry::new();
+        registry.register(4, Lines);
+        let source = InputSource::new(Some(path));
+        let outcomes = registry.run_day(4, &source).unwrap();
+        let answers: Vec<_> = outcomes
+            .iter()
+            .map(|o| (o.day, o.part, &o.answer))
+            .collect();
+        assert_eq!(
+            answers,
+            vec![(4, 1, &Answer::Integer(2)), (4, 2, &Answer::Integer(5))]
+        );
+        assert_eq!(
+            registry.run(4, 1, &source).unwrap().verdict,
+            Verdict::Unknown
+        );
+    }
+
+    #[derive(Debug, PartialEq, Subcommand)]
+    enum Extra {
+        Draw { size: usize },
+    }
+
+    #[test]
+    fn day_cli() {
+        let cli = DayCli::<Extra>::try_parse_from(["day", "input.txt"]).unwrap();
+        assert_eq!(cli.command, None);
+        assert_eq!(cli.input, Some(PathBuf::from("input.txt")));
+        let cli = DayCli::<Extra>::try_parse_from(["day", "draw", "3"]).unwrap();
+        assert_eq!(cli.command, Some(Extra::Draw { size: 3 }));
+        assert_eq!(cli.input, None);
+        assert!(DayCli::<Extra>::try_parse_from(["day", "draw", "x"]).is_err());
+        assert!(DayCli::<Extra>::try_parse_from(["day", "a", "b"]).is_err());
+    }
+
+    #[test]
+    fn solve_unknown() {
+        let registry = registry();
+        assert!(registry.solve(1, 3, "abc").is_err());
+        assert!(registry.solve(3, 1, "abc").is_err());
+        assert!(registry.run_day(3, &InputSource::default()).is_err());
+    }
+}
@@ -0,0 +1,280 @@
+use anyhow::{Context, Result, bail};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const RUNNER: &str = "src/bin/aoc/main.rs";
+const RESERVED_MODULES: [&str; 3] = ["main", "part1", "part2"];
+
+// Creates src/bin/dayN with the usual main.rs, part1.rs, part2.rs and domain module,
+// then adds the day to the aoc runner. Nothing is written when the day already exists.
+pub fn create_day(root: &Path, day: u8, module: &str) -> Result<Vec<PathBuf>> {
+    if !(1..=25).contains(&day) {
+        bail!("Day must be between 1 and 25, found {day}");
+    }
+    check_module_name(module)?;
+
+    let day_dir = root.join(format!("src/bin/day{day}"));
+    if day_dir.exists() {
+        bail!("{} already exists", day_dir.display());
+    }
+    let runner_path = root.join(RUNNER);
+    let runner = std::fs::read_to_string(&runner_path)
+        .with_context(|| format!("Cannot read {}", runner_path.display()))?;
+    let runner = register_day(&runner, day)?;
+
+    std::fs::create_dir_all(&day_dir)
+        .with_context(|| format!("Cannot create {}", day_dir.display()))?;
+    let files = [
+        ("main.rs".to_string(), main_rs(day, module)),
+        ("part1.rs".to_string(), part_rs(module)),
+        ("part2.rs".to_string(), part_rs(module)),
+        (format!("{module}.rs"), module_rs()),
+    ];
+    let mut created = Vec::new();
+    for (name, content) in files {
+        let path = day_dir.join(name);
+        OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(content.as_bytes()))
+            .with_context(|| format!("Cannot create {}", path.display()))?;
+        created.push(path);
+    }
+    std::fs::write(&runner_path, runner)
+        .with_context(|| format!("Cannot write {}", runner_path.display()))?;
+    created.push(runner_path);
+    Ok(created)
+}
+
+fn check_module_name(module: &str) -> Result<()> {
+    let mut chars = module.chars();
+    let valid_start = chars
+        .next()
+        .is_some_and(|ch| ch.is_ascii_lowercase() || ch == '_');
+    let valid_rest = chars.all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '_');
+    if !valid_start || !valid_rest {
+        bail!("'{module}' is not a valid snake_case module name");
+    }
+    if RESERVED_MODULES.contains(&module) {
+        bail!("'{module}' is already used by every day");
+    }
+    Ok(())
+}
+
+// Rewrites the day `mod` declarations and `register` calls of the runner to include `day`
+pub fn register_day(runner: &str, day: u8) -> Result<String> {
+    let lines: Vec<&str> = runner.lines().collect();
+    let is_mod = |line: &str| line.starts_with("mod day") && line.ends_with(';');
+    let is_register = |line: &str| {
+        let line = line.trim();
+        line.starts_with("day") && line.ends_with("::register(&mut registry);")
+    };
+
+    let mut days: Vec<u8> = lines
+        .iter()
+        .filter(|line| is_mod(line))
+        .map(|line| line["mod day".len()..line.len() - 1].parse::<u8>())
+        .collect::<Result<_, _>>()
+        .context("Unexpected day module in the runner")?;
+    if days.contains(&day) {
+        bail!("Day {day} is already registered in the runner");
+    }
+    days.push(day);
+
+    let (Some(first_mod), Some(last_mod)) = (
+        lines.iter().position(|line| is_mod(line)),
+        lines.iter().rposition(|line| is_mod(line)),
+    ) else {
+        bail!("No day modules found in the runner");
+    };
+    let (Some(first_register), Some(last_register)) = (
+        lines.iter().position(|line| is_register(line)),
+        lines.iter().rposition(|line| is_register(line)),
+    ) else {
+        bail!("No register calls found in the runner");
+    };
+    // Each `mod dayN;` is preceded by its #[allow(dead_code)] and #[path] attributes
+    let first_mod = first_mod.saturating_sub(2);
+
+    // Same order as rustfmt: modules sorted by name, register calls by day
+    let mut by_name = days.clone();
+    by_name.sort_by_key(|d| format!("day{d}"));
+    let mods: Vec<String> = by_name
+        .iter()
+        .map(|d| format!("#[allow(dead_code)]\n#[path = \"../day{d}/main.rs\"]\nmod day{d};"))
+        .collect();
+    days.sort();
+    let registers: Vec<String> = days
+        .iter()
+        .map(|d| format!("    day{d}::register(&mut registry);"))
+        .collect();
+
+    let mut out: Vec<String> = Vec::new();
+    out.extend(lines[..first_mod].iter().map(|l| l.to_string()));
+    out.extend(mods);
+    out.extend(
+        lines[last_mod + 1..first_register]
+            .iter()
+            .map(|l| l.to_string()),
+    );
+    out.extend(registers);
+    out.extend(lines[last_register + 1..].iter().map(|l| l.to_string()));
+    Ok(out.join("\n") + "\n")
+}
+
+fn main_rs(day: u8, module: &str) -> String {
+    let mut mods = [module, "part1", "part2"];
+    mods.sort();
+    let mods: String = mods.iter().map(|m| format!("mod {m};\n")).collect();
+    format!(
+        r#"{mods}
+use anyhow::Result;
+use common::registry::{{self, Registry}};
+use common::{{Answer, Solution}};
+
+pub struct Day{day};
+
+impl Solution for Day{day} {{
+    fn part1(&self, input: &str) -> Result<Answer> {{
+        part1::solve(input)
+    }}
+
+    fn part2(&self, input: &str) -> Result<Answer> {{
+        part2::solve(input)
+    }}
+}}
+
+pub fn register(registry: &mut Registry) {{
+    registry.register({day}, Day{day});
+}}
+
+fn main() -> Result<()> {{
+    registry::main_for(register)
+}}
+"#
+    )
+}
+
+fn part_rs(module: &str) -> String {
+    format!(
+        r#"use super::{module};
+use anyhow::Result;
+use common::Answer;
+
+pub fn solve(input: &str) -> Result<Answer> {{
+    let _lines = {module}::parse_lines(input)?;
+    Ok(Answer::None)
+}}
+"#
+    )
+}
+
+fn module_rs() -> String {
+    r#"use anyhow::Result;
+
+pub fn parse_lines(input: &str) -> Result<Vec<&str>> {
+    Ok(input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    #[test_case("", 0; "t1")]
+    #[test_case("a\n\n b \n", 2; "t2")]
+    fn parse_lines(input: &str, expected: usize) {
+        let result = super::parse_lines(input).unwrap();
+        assert_eq!(result.len(), expected);
+    }
+}
+"#
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    const RUNNER_SOURCE: &str = r#"use anyhow::Result;
+
+#[allow(dead_code)]
+#[path = "../day1/main.rs"]
+mod day1;
+#[allow(dead_code)]
+#[path = "../day2/main.rs"]
+mod day2;
+
+fn registry() -> Registry {
+    let mut registry = Registry::new();
+    day1::register(&mut registry);
+    day2::register(&mut registry);
+    registry
+}
+"#;
+
+    fn temp_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("aoc_scaffold_{name}_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("src/bin/aoc")).unwrap();
+        std::fs::create_dir_all(root.join("src/bin/day1")).unwrap();
+        std::fs::write(root.join(RUNNER), RUNNER_SOURCE).unwrap();
+        root
+    }
+
+    #[test]
+    fn register_day_keeps_rustfmt_order() {
+        let runner = register_day(RUNNER_SOURCE, 15).unwrap();
+        let mods: Vec<&str> = runner.lines().filter(|l| l.starts_with("mod ")).collect();
+        assert_eq!(mods, vec!["mod day1;", "mod day15;", "mod day2;"]);
+        assert!(runner.contains("#[path = \"../day15/main.rs\"]\nmod day15;"));
+        assert!(runner.contains(
+            "    day2::register(&mut registry);\n    day15::register(&mut registry);\n    registry"
+        ));
+    }
+
+    #[test]
+    fn register_day_twice() {
+        assert!(register_day(RUNNER_SOURCE, 2).is_err());
+    }
+
+    #[test]
+    fn create_day() {
+        let root = temp_root("create");
+        let created = super::create_day(&root, 15, "ingredients").unwrap();
+        assert_eq!(created.len(), 5);
+        let main = std::fs::read_to_string(root.join("src/bin/day15/main.rs")).unwrap();
+        assert!(main.starts_with("mod ingredients;\nmod part1;\nmod part2;\n"));
+        assert!(main.contains("registry.register(15, Day15);"));
+        assert!(main.contains("registry::main_for(register)"));
+        assert!(root.join("src/bin/day15/ingredients.rs").is_file());
+        let runner = std::fs::read_to_string(root.join(RUNNER)).unwrap();
+        assert!(runner.contains("day15::register(&mut registry);"));
+    }
+
+    #[test]
+    fn create_existing_day() {
+        let root = temp_root("existing");
+        assert!(super::create_day(&root, 1, "floor").is_err());
+        assert_eq!(
+            std::fs::read_to_string(root.join(RUNNER)).unwrap(),
+            RUNNER_SOURCE
+        );
+    }
+
+    #[test_case(0, "ingredients"; "day too low")]
+    #[test_case(26, "ingredients"; "day too high")]
+    #[test_case(15, "Ingredients"; "not snake case")]
+    #[test_case(15, "9lives"; "leading digit")]
+    #[test_case(15, "part1"; "reserved")]
+    fn create_day_rejects(day: u8, module: &str) {
+        let root = temp_root(&format!("rejects_{day}_{module}"));
+        assert!(super::create_day(&root, day, module).is_err());
+    }
+}
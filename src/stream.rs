@@ -0,0 +1,186 @@
+use crate::ParseError;
+use anyhow::Result;
+use std::collections::VecDeque;
+use std::io::{self, BufRead, ErrorKind};
+
+// Characters of the current line kept to give some context to an error
+const CONTEXT: usize = 30;
+
+// Streams the characters of a puzzle input, keeping track of where they came from so that
+// errors still point at a line and column without the whole input ever being in memory.
+pub struct Chars<R> {
+    reader: R,
+    line: usize,
+    column: usize,
+    context: VecDeque<char>,
+    last: Option<char>,
+}
+
+impl<R: BufRead> Chars<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            line: 1,
+            column: 0,
+            context: VecDeque::with_capacity(CONTEXT),
+            last: None,
+        }
+    }
+
+    // 1-based line and column of the last character returned
+    pub fn position(&self) -> (usize, usize) {
+        (self.line, self.column)
+    }
+
+    // Points at the last character returned, showing the end of its line read so far
+    pub fn error(&self, message: impl Into<String>) -> ParseError {
+        let text: String = self.context.iter().collect();
+        let start = match self.last {
+            Some('\n') | None => text.len(),
+            Some(ch) => text.len() - ch.len_utf8(),
+        };
+        let before = text[..start].chars().count();
+        let mut err = ParseError::new(&text, start..text.len(), message).with_line(self.line);
+        err.column_offset = self.column.saturating_sub(before + 1);
+        err
+    }
+
+    fn read_char(&mut self) -> io::Result<Option<char>> {
+        let Some(&first) = self.reader.fill_buf()?.first() else {
+            return Ok(None);
+        };
+        self.reader.consume(1);
+        let width = match first {
+            0x00..0x80 => 1,
+            0xC0..0xE0 => 2,
+            0xE0..0xF0 => 3,
+            0xF0..0xF8 => 4,
+            _ => 0,
+        };
+        let mut bytes = [first, 0, 0, 0];
+        if width > 1 {
+            self.reader
+                .read_exact(&mut bytes[1..width])
+                .map_err(|_| invalid_utf8())?;
+        }
+        let ch = std::str::from_utf8(&bytes[..width])
+            .ok()
+            .and_then(|s| s.chars().next())
+            .ok_or_else(invalid_utf8)?;
+
+        if self.last == Some('\n') {
+            self.line += 1;
+            self.column = 0;
+            self.context.clear();
+        }
+        self.column += 1;
+        if ch != '\n' {
+            if self.context.len() == CONTEXT {
+                self.context.pop_front();
+            }
+            self.context.push_back(ch);
+        }
+        self.last = Some(ch);
+        Ok(Some(ch))
+    }
+}
+
+fn invalid_utf8() -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, "Input is not valid UTF-8")
+}
+
+impl<R: BufRead> Iterator for Chars<R> {
+    type Item = io::Result<char>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_char().transpose()
+    }
+}
+
+// Streaming counterpart of `parse_error::parse_lines`, lines are parsed one at a time as they are read
+pub fn parse_lines<R: BufRead, T>(
+    reader: R,
+    mut parse: impl FnMut(&str) -> Result<T, ParseError>,
+) -> impl Iterator<Item = Result<T>> {
+    reader
+        .lines()
+        .enumerate()
+        .filter_map(move |(i, line)| match line {
+            Ok(line) if line.trim().is_empty() => None,
+            Ok(line) => Some(parse(&line).map_err(|err| err.with_line(i + 1).into())),
+            Err(err) => Some(Err(
+                anyhow::Error::new(err).context(format!("Cannot read line {}", i + 1))
+            )),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::BufReader;
+
+    #[test]
+    fn chars_across_lines() {
+        let mut chars = Chars::new("ab\nc".as_bytes());
+        let mut positions = Vec::new();
+        while let Some(ch) = chars.next() {
+            positions.push((ch.unwrap(), chars.position()));
+        }
+        assert_eq!(
+            positions,
+            vec![('a', (1, 1)), ('b', (1, 2)), ('\n', (1, 3)), ('c', (2, 1))]
+        );
+    }
+
+    #[test]
+    fn chars_split_across_reads() {
+        // A one byte buffer splits every multi-byte character
+        let reader = BufReader::with_capacity(1, "(é)→".as_bytes());
+        let chars: String = Chars::new(reader).map(Result::unwrap).collect();
+        assert_eq!(chars, "(é)→");
+    }
+
+    #[test]
+    fn chars_invalid_utf8() {
+        let mut chars = Chars::new(&[b'(', 0xFF][..]);
+        assert_eq!(chars.next().unwrap().unwrap(), '(');
+        assert_eq!(
+            chars.next().unwrap().unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn error_keeps_the_end_of_long_lines() {
+        let input = format!("()\n{}x", "(".repeat(100));
+        let mut chars = Chars::new(input.as_bytes());
+        while chars.next().unwrap().unwrap() != 'x' {}
+        let err = chars.error("Bad character");
+        assert_eq!(err.line, Some(2));
+        assert_eq!(err.column(), 101);
+        assert_eq!(err.text.len(), CONTEXT);
+        assert!(err.render().contains("| ...((("));
+    }
+
+    #[test]
+    fn error_on_newline() {
+        let mut chars = Chars::new("()\n".as_bytes());
+        chars.by_ref().take(3).for_each(drop);
+        let err = chars.error("Bad character");
+        assert_eq!((err.line, err.column()), (Some(1), 3));
+    }
+
+    #[test]
+    fn parse_lines_streams() {
+        let parse = |line: &str| {
+            line.parse::<u32>()
+                .map_err(|_| ParseError::at(line, line, "Expected a number"))
+        };
+        let mut lines = parse_lines("1\n\n2\nx\n3".as_bytes(), parse);
+        assert_eq!(lines.next().unwrap().unwrap(), 1);
+        assert_eq!(lines.next().unwrap().unwrap(), 2);
+        let err = lines.next().unwrap().unwrap_err();
+        assert_eq!(err.downcast_ref::<ParseError>().unwrap().line, Some(4));
+        assert_eq!(lines.next().unwrap().unwrap(), 3);
+    }
+}